@@ -0,0 +1,89 @@
+//! Benchmarks for the conversions, run with `cargo bench`.
+//!
+//! Uses criterion, which reports the time per iteration with confidence intervals and the change
+//! against the previous run. The cache benchmarks need the `lru` feature.
+use std::hint::black_box;
+use std::time::Instant;
+
+use criterion::{criterion_group, Criterion, Throughput};
+use kanji_hanzi_converter::*;
+
+/// Name, char conversion and string conversion of a direction.
+type Direction = (&'static str, fn(char) -> Option<char>, fn(&str) -> String);
+
+const SHORT: &str = "醫生在學校裡工作。";
+
+const DIRECTIONS: [Direction; 3] = [
+    (
+        "simplified",
+        convert_char_to_simplified,
+        convert_to_simplified_chinese,
+    ),
+    (
+        "traditional",
+        convert_char_to_traditional,
+        convert_to_traditional_chinese,
+    ),
+    (
+        "japanese",
+        convert_char_to_japanese,
+        convert_to_japanese_kanji,
+    ),
+];
+
+/// The kanji list is built lazily on first use, which happens only once per process, so it can't
+/// be sampled repeatedly like the other benchmarks. Prints the time of the single first call.
+fn first_call() {
+    let start = Instant::now();
+    black_box(is_known_kanji(black_box('学')));
+    println!("first call kanji list init: {:?}", start.elapsed());
+}
+
+fn directions(c: &mut Criterion) {
+    let document = SHORT.repeat(1024 * 1024 / SHORT.len());
+    for (name, convert_char, convert) in DIRECTIONS {
+        let mut group = c.benchmark_group(name);
+        group.bench_function("single char", |b| b.iter(|| convert_char(black_box('學'))));
+        group.bench_function("short string", |b| b.iter(|| convert(black_box(SHORT))));
+        group.throughput(Throughput::Bytes(document.len() as u64));
+        group.sample_size(20);
+        group.bench_function("1MB document", |b| b.iter(|| convert(black_box(&document))));
+        group.finish();
+    }
+}
+
+#[cfg(feature = "lru")]
+fn cache(c: &mut Criterion) {
+    // A repeating workload of a few hundred short strings
+    let inputs: Vec<String> = (0..500).map(|i| format!("{SHORT}{i}")).collect();
+    let uncached = ConverterBuilder::new().build();
+    let cached = ConverterBuilder::new().cache_capacity(1000).build();
+    let mut group = c.benchmark_group("500 repeated strings");
+    group.bench_function("uncached", |b| {
+        b.iter(|| {
+            for input in &inputs {
+                black_box(uncached.convert(black_box(input)));
+            }
+        })
+    });
+    group.bench_function("cached", |b| {
+        b.iter(|| {
+            for input in &inputs {
+                black_box(cached.convert_cached(black_box(input)));
+            }
+        })
+    });
+    group.finish();
+}
+
+#[cfg(not(feature = "lru"))]
+criterion_group!(benches, directions);
+#[cfg(feature = "lru")]
+criterion_group!(benches, directions, cache);
+
+fn main() {
+    // Has to run first, before any benchmark initializes the kanji list
+    first_call();
+    benches();
+    Criterion::default().configure_from_args().final_summary();
+}
@@ -0,0 +1,337 @@
+//! Parses the embedded mapping table at compile time, so the lookups don't need any
+//! initialization at runtime. A malformed line fails the build with its line number.
+//!
+//! Generates the array of entries, with every distinct candidate list embedded once, and for every
+//! direction an array of `(char, index)` sorted by char, which is searched with a binary search. The keys are the same as of
+//! `MappingTable::from_entries` with all lines, followed by `MappingTable::retain` with the kanji
+//! list. The kanji list is generated in file order, and the dataset version as hash of the
+//! mapping table.
+//!
+//! A chinese column is left out, if neither its direction feature, `direction-traditional` or
+//! `direction-simplified`, nor `direction-japanese` is enabled, which reads both columns.
+//!
+//! With the `minimal-data` feature, the further candidates that the single candidate conversions
+//! never return are left out.
+//!
+//! If the `KANJI_HANZI_CONVERTER_SUBSET` env var is set to the path of a file, only the kanji in
+//! that file and their entries are embedded.
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write;
+use std::{env, fs, path::Path};
+
+struct Entry {
+    japanese: char,
+    traditional_chinese: Vec<char>,
+    simplified_chinese: Vec<char>,
+}
+
+fn parse_candidates(field: &str) -> Vec<char> {
+    field
+        .split(',')
+        .filter_map(|s| {
+            let trimmed = s.trim();
+            if trimmed == "N/A" {
+                None
+            } else {
+                trimmed.chars().next()
+            }
+        })
+        .collect()
+}
+
+/// Parses a line, failing the build if it's malformed.
+fn parse_line(line: &str, line_number: usize) -> Entry {
+    let parts: Vec<&str> = line.split('\t').collect();
+    if parts.len() != 3 {
+        panic!(
+            "kanji_mapping_table.txt line {line_number}: expected 3 tab separated fields, found {}",
+            parts.len()
+        );
+    }
+    let mut japanese = parts[0].trim().chars();
+    let (Some(first), None) = (japanese.next(), japanese.next()) else {
+        panic!("kanji_mapping_table.txt line {line_number}: the japanese field must be one char");
+    };
+    Entry {
+        japanese: first,
+        traditional_chinese: parse_candidates(parts[1]),
+        simplified_chinese: parse_candidates(parts[2]),
+    }
+}
+
+/// Parses all lines, skipping empty lines and the header enclosed in lines of dashes, like
+/// `load_mapping_from_str`.
+fn parse_mapping(mapping: &str) -> Vec<Entry> {
+    let mut entries = Vec::new();
+    let mut in_header = false;
+    for (line_number, line) in mapping.lines().enumerate() {
+        if !line.is_empty() && line.chars().all(|cha| cha == '-') {
+            in_header = !in_header;
+            continue;
+        }
+        if in_header || line.trim().is_empty() {
+            continue;
+        }
+        entries.push(parse_line(line, line_number + 1));
+    }
+    entries
+}
+
+/// Interns the candidate lists, so every distinct list is embedded once as `CANDIDATES_<id>`.
+#[derive(Default)]
+struct Candidates {
+    ids: HashMap<Vec<char>, usize>,
+    lists: Vec<Vec<char>>,
+}
+
+impl Candidates {
+    fn intern(&mut self, candidates: &[char]) -> usize {
+        if let Some(id) = self.ids.get(candidates) {
+            return *id;
+        }
+        let id = self.lists.len();
+        self.ids.insert(candidates.to_vec(), id);
+        self.lists.push(candidates.to_vec());
+        id
+    }
+
+    fn write(&self, out: &mut String) {
+        for (id, list) in self.lists.iter().enumerate() {
+            writeln!(out, "static CANDIDATES_{id}: &[char] = &{list:?};").unwrap();
+        }
+    }
+}
+
+fn entry_literal(entry: &Entry, candidates: &mut Candidates) -> String {
+    format!(
+        "Entry {{ japanese: {:?}, traditional_chinese: Cow::Borrowed(CANDIDATES_{}), simplified_chinese: Cow::Borrowed(CANDIDATES_{}) }}",
+        entry.japanese,
+        candidates.intern(&entry.traditional_chinese),
+        candidates.intern(&entry.simplified_chinese)
+    )
+}
+
+fn write_keys(out: &mut String, name: &str, keys: &BTreeMap<char, usize>) {
+    writeln!(out, "static {name}: &[(char, u32)] = &[").unwrap();
+    for (key, index) in keys {
+        writeln!(out, "    ({key:?}, {index}),").unwrap();
+    }
+    writeln!(out, "];").unwrap();
+}
+
+/// The japanese kanji and the first candidates of the entries, which are the chars with a mapping
+/// of their own.
+fn primary_chars(entries: &[Entry]) -> HashSet<char> {
+    let mut primary = HashSet::new();
+    for entry in entries {
+        primary.insert(entry.japanese);
+        primary.extend(entry.traditional_chinese.first());
+        primary.extend(entry.simplified_chinese.first());
+    }
+    primary
+}
+
+/// The lowest candidate position wins, on a tie the first entry. Further candidates in `primary`
+/// are skipped.
+fn insert_candidates(
+    keys: &mut BTreeMap<char, (usize, usize)>,
+    candidates: &[char],
+    index: usize,
+    primary: &HashSet<char>,
+) {
+    for (position, val) in candidates.iter().enumerate() {
+        if position > 0 && primary.contains(val) {
+            continue;
+        }
+        let existing = keys.entry(*val).or_insert((position, index));
+        if position < existing.0 {
+            *existing = (position, index);
+        }
+    }
+}
+
+/// Removes the further candidates that are never returned by the single candidate conversions.
+/// A further candidate is only returned for the char itself, if it resolves to the entry listing
+/// it, e.g. 甚 for 甚, so the others are dead data for them.
+fn strip_further_candidates(
+    entries: &mut [Entry],
+    japanese_keys: &BTreeMap<char, usize>,
+    traditional_keys: &BTreeMap<char, usize>,
+    simplified_keys: &BTreeMap<char, usize>,
+) {
+    // The entry of a char, checking the keys of the target script first like `entry_for`
+    let resolves_to = |cha: char, first: &BTreeMap<char, usize>, second: &BTreeMap<char, usize>| {
+        if japanese_keys.contains_key(&cha) {
+            return None;
+        }
+        first.get(&cha).or_else(|| second.get(&cha)).copied()
+    };
+    for (index, entry) in entries.iter_mut().enumerate() {
+        let mut position = 0;
+        entry.traditional_chinese.retain(|cha| {
+            position += 1;
+            position == 1 || resolves_to(*cha, traditional_keys, simplified_keys) == Some(index)
+        });
+        let mut position = 0;
+        entry.simplified_chinese.retain(|cha| {
+            position += 1;
+            position == 1 || resolves_to(*cha, simplified_keys, traditional_keys) == Some(index)
+        });
+    }
+}
+
+/// Generates the sorted `(compatibility, unified)` pairs of `cjk_compatibility_ideographs.txt`.
+fn write_compatibility_ideographs(out: &mut String) {
+    let data = fs::read_to_string("cjk_compatibility_ideographs.txt").unwrap();
+    let mut pairs = BTreeMap::new();
+    for line in data.lines() {
+        if line.starts_with('#') || line.trim().is_empty() {
+            continue;
+        }
+        let mut chars = line.split('\t').filter_map(|field| field.chars().next());
+        let (Some(compatibility), Some(unified)) = (chars.next(), chars.next()) else {
+            panic!("cjk_compatibility_ideographs.txt: malformed line {line:?}");
+        };
+        pairs.insert(compatibility, unified);
+    }
+    writeln!(
+        out,
+        "pub(crate) static COMPATIBILITY_IDEOGRAPHS: &[(char, char)] = &["
+    )
+    .unwrap();
+    for (compatibility, unified) in pairs {
+        writeln!(out, "    ({compatibility:?}, {unified:?}),").unwrap();
+    }
+    writeln!(out, "];").unwrap();
+}
+
+/// The kanji of the file in `KANJI_HANZI_CONVERTER_SUBSET`, if set. Whitespace is ignored, so
+/// the kanji can be on one line or one per line.
+fn read_subset() -> Option<HashSet<char>> {
+    let path = env::var_os("KANJI_HANZI_CONVERTER_SUBSET")?;
+    println!("cargo:rerun-if-changed={}", Path::new(&path).display());
+    let subset = fs::read_to_string(&path).unwrap_or_else(|err| {
+        panic!(
+            "KANJI_HANZI_CONVERTER_SUBSET: can't read {}: {err}",
+            Path::new(&path).display()
+        )
+    });
+    Some(subset.chars().filter(|cha| !cha.is_whitespace()).collect())
+}
+
+/// FNV-1a hash of the mapping table, which identifies the dataset version.
+fn dataset_hash(data: &str) -> u64 {
+    data.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=kanji_mapping_table.txt");
+    println!("cargo:rerun-if-changed=kanji_list_topological.txt");
+    println!("cargo:rerun-if-changed=cjk_compatibility_ideographs.txt");
+    println!("cargo:rerun-if-env-changed=KANJI_HANZI_CONVERTER_SUBSET");
+
+    let mapping = fs::read_to_string("kanji_mapping_table.txt").unwrap();
+    let mut kanji_order: Vec<char> = fs::read_to_string("kanji_list_topological.txt")
+        .unwrap()
+        .lines()
+        .filter_map(|line| line.trim().chars().next())
+        .collect();
+    if let Some(subset) = read_subset() {
+        kanji_order.retain(|kanji| subset.contains(kanji));
+    }
+    let kanji_list: HashSet<char> = kanji_order.iter().copied().collect();
+
+    let all_entries = parse_mapping(&mapping);
+    let validated = all_entries.len();
+    let mut entries: Vec<Entry> = all_entries
+        .into_iter()
+        .filter(|entry| kanji_list.contains(&entry.japanese))
+        .collect();
+    // Taken before leaving out columns, so a char keeps resolving through its own entry
+    let primary = primary_chars(&entries);
+    // A column that no enabled direction reads is not embedded, so its chars are also not keys
+    let japanese = env::var_os("CARGO_FEATURE_DIRECTION_JAPANESE").is_some();
+    let traditional = japanese || env::var_os("CARGO_FEATURE_DIRECTION_TRADITIONAL").is_some();
+    let simplified = japanese || env::var_os("CARGO_FEATURE_DIRECTION_SIMPLIFIED").is_some();
+    for entry in &mut entries {
+        if !traditional {
+            entry.traditional_chinese.clear();
+        }
+        if !simplified {
+            entry.simplified_chinese.clear();
+        }
+    }
+
+    let mut japanese_keys = BTreeMap::new();
+    let mut simplified_keys = BTreeMap::new();
+    let mut traditional_keys = BTreeMap::new();
+    for (index, entry) in entries.iter().enumerate() {
+        japanese_keys.insert(entry.japanese, index);
+        insert_candidates(
+            &mut traditional_keys,
+            &entry.traditional_chinese,
+            index,
+            &primary,
+        );
+        insert_candidates(
+            &mut simplified_keys,
+            &entry.simplified_chinese,
+            index,
+            &primary,
+        );
+    }
+    let without_position = |keys: BTreeMap<char, (usize, usize)>| {
+        keys.into_iter()
+            .map(|(key, (_, index))| (key, index))
+            .collect()
+    };
+    let simplified_keys = without_position(simplified_keys);
+    let traditional_keys = without_position(traditional_keys);
+
+    if env::var_os("CARGO_FEATURE_MINIMAL_DATA").is_some() {
+        strip_further_candidates(
+            &mut entries,
+            &japanese_keys,
+            &traditional_keys,
+            &simplified_keys,
+        );
+    }
+
+    let mut out = String::new();
+    writeln!(
+        out,
+        "// Validated {validated} lines of kanji_mapping_table.txt"
+    )
+    .unwrap();
+    let mut candidates = Candidates::default();
+    writeln!(out, "static ENTRIES: &[Entry] = &[").unwrap();
+    for entry in &entries {
+        writeln!(out, "    {},", entry_literal(entry, &mut candidates)).unwrap();
+    }
+    writeln!(out, "];").unwrap();
+    candidates.write(&mut out);
+    write_keys(&mut out, "JAPANESE_KEYS", &japanese_keys);
+    write_keys(&mut out, "SIMPLIFIED_KEYS", &simplified_keys);
+    write_keys(&mut out, "TRADITIONAL_KEYS", &traditional_keys);
+    writeln!(
+        out,
+        "pub(crate) static KANJI_LIST: &[char] = &{kanji_order:?};"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "pub(crate) static DATASET_VERSION: &str = \"fnv1a64:{:016x}\";",
+        dataset_hash(&mapping)
+    )
+    .unwrap();
+
+    if env::var_os("CARGO_FEATURE_COMPAT_IDEOGRAPHS").is_some() {
+        write_compatibility_ideographs(&mut out);
+    }
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("mapping_table.rs"), out).unwrap();
+}
@@ -0,0 +1,23 @@
+//! Feeds arbitrary input to the parser of custom mapping tables, which must never panic.
+#![no_main]
+
+use kanji_hanzi_converter::{load_mapping_from_str, Entry};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return;
+    };
+    for line in text.lines() {
+        if let Some(entry) = Entry::from_line(line) {
+            // A parsed entry must survive a roundtrip through its Display impl
+            let reparsed = Entry::from_line(&entry.to_string());
+            assert_eq!(reparsed.map(|reparsed| reparsed.japanese), Some(entry.japanese));
+        }
+    }
+    if let Ok(table) = load_mapping_from_str(text) {
+        let _ = table.convert_to_simplified_chinese(text);
+        let _ = table.convert_to_traditional_chinese(text);
+        let _ = table.convert_to_japanese_kanji(text);
+    }
+});
@@ -0,0 +1,49 @@
+//! Converts string literals at compile time, so there's no runtime cost.
+//!
+//! ```
+//! use kanji_hanzi_converter_macros::{to_japanese, to_simplified, to_traditional};
+//!
+//! const LABEL: &str = to_simplified!("醫生");
+//! assert_eq!(LABEL, "医生");
+//! assert_eq!(to_traditional!("学校"), "學校");
+//! assert_eq!(to_japanese!("學校"), "学校");
+//! ```
+//!
+//! Only string literals are supported, anything else is a compile error:
+//!
+//! ```compile_fail
+//! use kanji_hanzi_converter_macros::to_simplified;
+//!
+//! let input = "醫生";
+//! to_simplified!(input);
+//! ```
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, LitStr};
+
+/// Converts a string literal to Simplified Chinese, see
+/// `kanji_hanzi_converter::convert_to_simplified_chinese`.
+#[proc_macro]
+pub fn to_simplified(input: TokenStream) -> TokenStream {
+    convert_literal(input, kanji_hanzi_converter::convert_to_simplified_chinese)
+}
+
+/// Converts a string literal to Traditional Chinese, see
+/// `kanji_hanzi_converter::convert_to_traditional_chinese`.
+#[proc_macro]
+pub fn to_traditional(input: TokenStream) -> TokenStream {
+    convert_literal(input, kanji_hanzi_converter::convert_to_traditional_chinese)
+}
+
+/// Converts a string literal to Japanese Kanji, see
+/// `kanji_hanzi_converter::convert_to_japanese_kanji`.
+#[proc_macro]
+pub fn to_japanese(input: TokenStream) -> TokenStream {
+    convert_literal(input, kanji_hanzi_converter::convert_to_japanese_kanji)
+}
+
+fn convert_literal(input: TokenStream, convert: fn(&str) -> String) -> TokenStream {
+    let literal = parse_macro_input!(input as LitStr);
+    let converted = LitStr::new(&convert(&literal.value()), literal.span());
+    quote!(#converted).into()
+}
@@ -0,0 +1,130 @@
+//! Converts text from stdin or a file and writes it to stdout.
+//!
+//! `echo 醫生 | kanji_hanzi_converter --to simplified`
+// Without any direction every target is rejected, so nothing is converted
+#![cfg_attr(
+    not(any(
+        feature = "direction-simplified",
+        feature = "direction-traditional",
+        feature = "direction-japanese"
+    )),
+    allow(unreachable_code, unused_mut, unused_variables)
+)]
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use std::process::ExitCode;
+
+use clap::{Parser, ValueEnum};
+#[cfg(feature = "direction-japanese")]
+use kanji_hanzi_converter::convert_stream_to_japanese;
+#[cfg(feature = "direction-simplified")]
+use kanji_hanzi_converter::convert_stream_to_simplified;
+#[cfg(feature = "direction-traditional")]
+use kanji_hanzi_converter::convert_stream_to_traditional;
+use kanji_hanzi_converter::TargetScript;
+
+/// Converts between Japanese Kanji, Traditional Chinese and Simplified Chinese.
+///
+/// Reads from stdin if no file is given, and writes to stdout.
+#[derive(Debug, PartialEq, Eq, Parser)]
+#[command(version)]
+struct Args {
+    /// The script to convert to.
+    #[arg(long = "to", value_enum)]
+    target: Target,
+    /// The file to convert instead of stdin.
+    #[arg(long)]
+    file: Option<String>,
+}
+
+/// The values of `--to`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Target {
+    #[cfg(feature = "direction-japanese")]
+    Japanese,
+    #[cfg(feature = "direction-traditional")]
+    Traditional,
+    #[cfg(feature = "direction-simplified")]
+    Simplified,
+}
+
+impl From<Target> for TargetScript {
+    fn from(target: Target) -> Self {
+        match target {
+            #[cfg(feature = "direction-japanese")]
+            Target::Japanese => TargetScript::Japanese,
+            #[cfg(feature = "direction-traditional")]
+            Target::Traditional => TargetScript::Traditional,
+            #[cfg(feature = "direction-simplified")]
+            Target::Simplified => TargetScript::Simplified,
+        }
+    }
+}
+
+fn run(args: Args) -> io::Result<()> {
+    let reader: Box<dyn Read> = match &args.file {
+        Some(path) => Box::new(File::open(path)?),
+        None => Box::new(io::stdin().lock()),
+    };
+    let mut writer = BufWriter::new(io::stdout().lock());
+    match TargetScript::from(args.target) {
+        #[cfg(feature = "direction-japanese")]
+        TargetScript::Japanese => convert_stream_to_japanese(reader, &mut writer)?,
+        #[cfg(feature = "direction-traditional")]
+        TargetScript::Traditional => convert_stream_to_traditional(reader, &mut writer)?,
+        #[cfg(feature = "direction-simplified")]
+        TargetScript::Simplified => convert_stream_to_simplified(reader, &mut writer)?,
+    }
+    writer.flush()
+}
+
+fn main() -> ExitCode {
+    if let Err(err) = run(Args::parse()) {
+        eprintln!("{err}");
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
+
+#[cfg(all(test, feature = "direction-japanese", feature = "direction-simplified"))]
+mod tests {
+    use clap::error::ErrorKind;
+    use clap::CommandFactory;
+
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, clap::Error> {
+        Args::try_parse_from(std::iter::once("kanji_hanzi_converter").chain(args.iter().copied()))
+    }
+
+    #[test]
+    fn parse_arguments() {
+        assert_eq!(
+            parse(&["--to", "simplified"]).unwrap(),
+            Args {
+                target: Target::Simplified,
+                file: None
+            }
+        );
+        assert_eq!(
+            parse(&["--file", "in.txt", "--to", "japanese"]).unwrap(),
+            Args {
+                target: Target::Japanese,
+                file: Some("in.txt".to_string())
+            }
+        );
+        assert!(parse(&[]).is_err());
+        assert!(parse(&["--to", "korean"]).is_err());
+        assert!(parse(&["--to"]).is_err());
+    }
+
+    #[test]
+    fn help() {
+        assert_eq!(
+            parse(&["--help"]).unwrap_err().kind(),
+            ErrorKind::DisplayHelp
+        );
+        assert_eq!(parse(&["-h"]).unwrap_err().kind(), ErrorKind::DisplayHelp);
+        Args::command().debug_assert();
+    }
+}
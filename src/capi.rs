@@ -0,0 +1,117 @@
+//! C API, enabled with the `capi` feature.
+//!
+//! All strings are null-terminated UTF-8. The returned strings are owned by the caller and must be
+//! released with [khc_free], not with the `free` of the C runtime. If the input is null or not
+//! valid UTF-8, null is returned.
+//!
+//! To link it from C, build the crate e.g. as static library with
+//! `cargo rustc --release --features capi --crate-type staticlib`.
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+
+#[cfg(feature = "direction-japanese")]
+use crate::convert_to_japanese_kanji;
+#[cfg(feature = "direction-simplified")]
+use crate::convert_to_simplified_chinese;
+#[cfg(feature = "direction-traditional")]
+use crate::convert_to_traditional_chinese;
+
+/// Converts the input to Simplified Chinese, see [crate::convert_to_simplified_chinese].
+///
+/// # Safety
+/// `input` must be null or point to a null-terminated string.
+#[cfg(feature = "direction-simplified")]
+#[no_mangle]
+pub unsafe extern "C" fn khc_to_simplified(input: *const c_char) -> *mut c_char {
+    convert_c_str(input, convert_to_simplified_chinese)
+}
+
+/// Converts the input to Traditional Chinese, see [crate::convert_to_traditional_chinese].
+///
+/// # Safety
+/// `input` must be null or point to a null-terminated string.
+#[cfg(feature = "direction-traditional")]
+#[no_mangle]
+pub unsafe extern "C" fn khc_to_traditional(input: *const c_char) -> *mut c_char {
+    convert_c_str(input, convert_to_traditional_chinese)
+}
+
+/// Converts the input to Japanese Kanji, see [crate::convert_to_japanese_kanji].
+///
+/// # Safety
+/// `input` must be null or point to a null-terminated string.
+#[cfg(feature = "direction-japanese")]
+#[no_mangle]
+pub unsafe extern "C" fn khc_to_japanese(input: *const c_char) -> *mut c_char {
+    convert_c_str(input, convert_to_japanese_kanji)
+}
+
+/// Releases a string returned by this API. Null is ignored.
+///
+/// # Safety
+/// `ptr` must be null or a string returned by this API, which has not been released yet.
+#[no_mangle]
+pub unsafe extern "C" fn khc_free(ptr: *mut c_char) {
+    if !ptr.is_null() {
+        drop(CString::from_raw(ptr));
+    }
+}
+
+unsafe fn convert_c_str(input: *const c_char, convert: fn(&str) -> String) -> *mut c_char {
+    if input.is_null() {
+        return ptr::null_mut();
+    }
+    let Ok(input) = CStr::from_ptr(input).to_str() else {
+        return ptr::null_mut();
+    };
+    // The output can't contain a null byte, since the input doesn't and only CJK chars are
+    // replaced.
+    match CString::new(convert(input)) {
+        Ok(output) => output.into_raw(),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+#[cfg(all(test, feature = "direction-simplified"))]
+mod tests {
+    use super::*;
+
+    fn call(f: unsafe extern "C" fn(*const c_char) -> *mut c_char, input: &CStr) -> Option<String> {
+        unsafe {
+            let output = f(input.as_ptr());
+            if output.is_null() {
+                return None;
+            }
+            let converted = CStr::from_ptr(output).to_str().unwrap().to_string();
+            khc_free(output);
+            Some(converted)
+        }
+    }
+
+    #[cfg(all(
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn convert_through_ffi() {
+        let input = CString::new("醫生 學校").unwrap();
+        assert_eq!(
+            call(khc_to_simplified, &input).as_deref(),
+            Some("医生 学校")
+        );
+        assert_eq!(call(khc_to_japanese, &input).as_deref(), Some("医生 学校"));
+        let input = CString::new("学校").unwrap();
+        assert_eq!(call(khc_to_traditional, &input).as_deref(), Some("學校"));
+    }
+
+    #[test]
+    fn null_and_invalid_input() {
+        unsafe {
+            assert!(khc_to_simplified(ptr::null()).is_null());
+            khc_free(ptr::null_mut());
+        }
+        let invalid = CString::new(vec![b'a', 0xff]).unwrap();
+        assert_eq!(call(khc_to_simplified, &invalid), None);
+    }
+}
@@ -0,0 +1,69 @@
+use alloc::vec::Vec;
+
+use crate::{Entry, FnvHashMap};
+
+/// A map from char to [Entry].
+///
+/// The keys are stored as array sorted by char, with an index into the entries of the mapping
+/// table, so every entry is stored only once, even if it is keyed by multiple chars.
+#[derive(Debug, Clone, Copy)]
+pub struct CharMap<'a> {
+    keys: &'a [(char, u32)],
+    entries: &'a [Entry],
+}
+
+/// Converts a map from char to entry index into keys sorted by char.
+pub(crate) fn sorted_keys(map: FnvHashMap<char, u32>) -> Vec<(char, u32)> {
+    let mut keys: Vec<(char, u32)> = map.into_iter().collect();
+    keys.sort_unstable_by_key(|(key, _)| *key);
+    keys
+}
+
+impl<'a> CharMap<'a> {
+    /// `keys` need to be sorted by char, without duplicate keys.
+    pub(crate) fn new(keys: &'a [(char, u32)], entries: &'a [Entry]) -> Self {
+        CharMap { keys, entries }
+    }
+
+    pub fn get(&self, key: &char) -> Option<&'a Entry> {
+        self.get_index(key).map(|index| &self.entries[index])
+    }
+
+    /// The index of the entry of the key in [crate::MappingTable::entries].
+    pub fn get_index(&self, key: &char) -> Option<usize> {
+        self.keys
+            .binary_search_by_key(key, |(key, _)| *key)
+            .ok()
+            .map(|pos| self.keys[pos].1 as usize)
+    }
+
+    pub fn contains_key(&self, key: &char) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Iterates over the keys and entries, sorted by key.
+    pub fn iter(&self) -> impl Iterator<Item = (&'a char, &'a Entry)> {
+        let entries = self.entries;
+        self.keys
+            .iter()
+            .map(move |(key, index)| (key, &entries[*index as usize]))
+    }
+
+    /// Iterates over the keys in sorted order.
+    pub fn keys(&self) -> impl Iterator<Item = &'a char> {
+        self.keys.iter().map(|(key, _)| key)
+    }
+
+    /// Iterates over the entries, sorted by key. An entry is returned once per key.
+    pub fn values(&self) -> impl Iterator<Item = &'a Entry> {
+        self.iter().map(|(_, entry)| entry)
+    }
+}
@@ -0,0 +1,908 @@
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::str::FromStr;
+#[cfg(feature = "lru")]
+use std::sync::Mutex;
+
+#[cfg(feature = "lru")]
+use crate::lru::LruCache;
+use crate::{
+    get_mapping_table, is_cjk_ideograph, is_kana, is_known_kanji, load_mapping_from_str,
+    to_full_width_katakana, to_half_width, with_sound_mark, FnvHashMap, MappingTable, ParseError,
+    TargetScript,
+};
+
+/// What to do with a char that has no mapping to the target script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnknownAction {
+    /// Leave the char unchanged.
+    #[default]
+    Keep,
+    /// Replace the char with the given char.
+    Replace(char),
+    /// Drop the char from the output.
+    Skip,
+}
+
+/// What to do with a kokuji, a kanji of the japanese kanji list without chinese equivalent, like
+/// 峠. See [crate::convert_to_simplified_reporting_kokuji].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum KokujiAction {
+    /// Leave the kokuji unchanged.
+    #[default]
+    Keep,
+    /// Drop the kokuji from the output.
+    Drop,
+    /// Replace the kokuji with the given string, e.g. a romanization or a placeholder.
+    Replace(String),
+}
+
+/// How to handle readings (furigana) in parentheses after kanji, like `漢字(かんじ)`.
+///
+/// A reading is a parenthesized group of kana directly after a CJK ideograph, with ASCII or
+/// full-width parentheses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Furigana {
+    /// Convert readings like any other text.
+    #[default]
+    Convert,
+    /// Leave readings untouched, including the parentheses.
+    Keep,
+    /// Remove readings, including the parentheses.
+    Strip,
+}
+
+/// Builder for a [Converter].
+///
+/// ```
+/// # #[cfg(feature = "direction-traditional")]
+/// # {
+/// use kanji_hanzi_converter::{ConverterBuilder, TargetScript, UnknownAction};
+///
+/// let converter = ConverterBuilder::new()
+///     .target(TargetScript::Traditional)
+///     .on_unknown(UnknownAction::Keep)
+///     .normalize_width(true)
+///     .build();
+/// assert_eq!(converter.convert("学校１"), "學校1");
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConverterBuilder {
+    table: Option<Arc<MappingTable>>,
+    target: TargetScript,
+    on_unknown: UnknownAction,
+    on_kokuji: Option<KokujiAction>,
+    normalize_width: bool,
+    widen_katakana: bool,
+    normalize_daiji: bool,
+    conservative: bool,
+    skip_non_cjk: bool,
+    strip_variation_selectors: bool,
+    furigana: Furigana,
+    expand_iteration_mark: bool,
+    #[cfg(feature = "compat-ideographs")]
+    normalize_compatibility: bool,
+    overrides: Vec<(char, char)>,
+    preserve: Vec<char>,
+    phrases: Vec<(String, String)>,
+    #[cfg(feature = "lru")]
+    cache_capacity: usize,
+}
+
+#[cfg(feature = "direction-simplified")]
+const DEFAULT_TARGET: TargetScript = TargetScript::Simplified;
+#[cfg(all(
+    not(feature = "direction-simplified"),
+    feature = "direction-traditional"
+))]
+const DEFAULT_TARGET: TargetScript = TargetScript::Traditional;
+#[cfg(all(
+    not(feature = "direction-simplified"),
+    not(feature = "direction-traditional")
+))]
+const DEFAULT_TARGET: TargetScript = TargetScript::Japanese;
+
+impl Default for ConverterBuilder {
+    fn default() -> Self {
+        ConverterBuilder {
+            table: None,
+            target: DEFAULT_TARGET,
+            on_unknown: UnknownAction::Keep,
+            on_kokuji: None,
+            normalize_width: false,
+            widen_katakana: false,
+            normalize_daiji: false,
+            conservative: false,
+            skip_non_cjk: false,
+            strip_variation_selectors: false,
+            furigana: Furigana::Convert,
+            expand_iteration_mark: false,
+            #[cfg(feature = "compat-ideographs")]
+            normalize_compatibility: false,
+            overrides: Vec::new(),
+            preserve: Vec::new(),
+            phrases: Vec::new(),
+            #[cfg(feature = "lru")]
+            cache_capacity: 0,
+        }
+    }
+}
+
+impl ConverterBuilder {
+    /// Converts to Simplified Chinese, keeps unknown chars and doesn't normalize the width.
+    /// Without the `direction-simplified` feature, it converts to the first enabled direction of
+    /// Traditional Chinese and Japanese.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The mapping table to use instead of the embedded one, e.g. loaded with
+    /// [load_mapping_from_str]. An `Arc` allows to share the table between converters.
+    pub fn table(mut self, table: impl Into<Arc<MappingTable>>) -> Self {
+        self.table = Some(table.into());
+        self
+    }
+
+    /// The script to convert to.
+    pub fn target(mut self, target: TargetScript) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// What to do with chars that have no mapping to the target script. This includes non CJK
+    /// chars like ASCII.
+    pub fn on_unknown(mut self, on_unknown: UnknownAction) -> Self {
+        self.on_unknown = on_unknown;
+        self
+    }
+
+    /// What to do with kokuji, which can't be converted to chinese. By default they are unknown
+    /// chars, see [Self::on_unknown].
+    ///
+    /// [KokujiAction::Replace] only applies to [Converter::convert], [Converter::convert_char]
+    /// keeps the kokuji instead, since the replacement is a string.
+    pub fn on_kokuji(mut self, on_kokuji: KokujiAction) -> Self {
+        self.on_kokuji = Some(on_kokuji);
+        self
+    }
+
+    /// Also map full-width ASCII to half-width and the ideographic space to a regular space, see
+    /// [to_half_width]. Normalized chars don't count as unknown.
+    pub fn normalize_width(mut self, normalize_width: bool) -> Self {
+        self.normalize_width = normalize_width;
+        self
+    }
+
+    /// Map half-width katakana (U+FF61–U+FF9F) to full-width, and combine them with a following
+    /// sound mark, see [crate::widen_katakana]. [Converter::convert_char] doesn't combine sound marks,
+    /// since it only sees a single char. Widened chars don't count as unknown.
+    pub fn widen_katakana(mut self, widen_katakana: bool) -> Self {
+        self.widen_katakana = widen_katakana;
+        self
+    }
+
+    /// Replace the daiji (大字), the numerals of financial and legal documents like 壹 or 貳, with
+    /// the common numerals like 一 or 二, in the form of the target script. 萬 is kept when
+    /// converting to Traditional Chinese, where it is the common form.
+    ///
+    /// Some daiji are also regular kanji, like 参 in 参加 or 陸 (land), so this should only be
+    /// enabled for text where they are numerals.
+    pub fn normalize_daiji(mut self, normalize_daiji: bool) -> Self {
+        self.normalize_daiji = normalize_daiji;
+        self
+    }
+
+    /// Leave chars unchanged, whose entry has more than one candidate for the target script,
+    /// instead of converting them to the first candidate, e.g. 医 to traditional, which can be 醫
+    /// or 毉. Conversions to Japanese are never ambiguous. [Self::overrides] still apply.
+    pub fn conservative(mut self, conservative: bool) -> Self {
+        self.conservative = conservative;
+        self
+    }
+
+    /// Only convert chars in the CJK Unified Ideographs blocks, see [is_cjk_ideograph]. All other
+    /// chars, including CJK punctuation and kana, are left untouched, even if they have a mapping
+    /// or would be affected by [Self::on_unknown] or [Self::normalize_width].
+    pub fn skip_non_cjk(mut self, skip_non_cjk: bool) -> Self {
+        self.skip_non_cjk = skip_non_cjk;
+        self
+    }
+
+    /// Remove variation selectors (U+FE00–U+FE0F and the ideographic variation selectors
+    /// U+E0100–U+E01EF), which select a glyph variant of the preceding char. Otherwise they are
+    /// kept, since they are not treated as unknown chars. In both cases the preceding char is
+    /// converted as usual.
+    pub fn strip_variation_selectors(mut self, strip_variation_selectors: bool) -> Self {
+        self.strip_variation_selectors = strip_variation_selectors;
+        self
+    }
+
+    /// How to handle readings in parentheses after kanji, see [Furigana].
+    pub fn furigana(mut self, furigana: Furigana) -> Self {
+        self.furigana = furigana;
+        self
+    }
+
+    /// Replace the iteration mark 々 with the converted form of the preceding kanji, e.g. 時々
+    /// becomes 时时 when converting to simplified. A 々 that doesn't follow a kanji is converted
+    /// like any other char. This only applies to [Converter::convert], since a single char has
+    /// no preceding char.
+    pub fn expand_iteration_mark(mut self, expand_iteration_mark: bool) -> Self {
+        self.expand_iteration_mark = expand_iteration_mark;
+        self
+    }
+
+    /// Normalize CJK Compatibility Ideographs to their unified form before the conversion, see
+    /// [crate::normalize_compatibility_ideograph]. Otherwise they are unknown chars.
+    #[cfg(feature = "compat-ideographs")]
+    pub fn normalize_compatibility(mut self, normalize_compatibility: bool) -> Self {
+        self.normalize_compatibility = normalize_compatibility;
+        self
+    }
+
+    /// Mappings `(from, to)` that take precedence over the mapping table. If a char is overridden
+    /// multiple times, the last override wins.
+    ///
+    /// An override only applies to the exact input char. A char that is a key of multiple entries
+    /// is overridden regardless of which entry it would resolve to, while other chars of the same
+    /// entries are still converted by the mapping table.
+    pub fn overrides(mut self, overrides: &[(char, char)]) -> Self {
+        self.overrides.extend_from_slice(overrides);
+        self
+    }
+
+    /// Chars that are never converted, e.g. the kanji of a name, whose chinese form would be wrong
+    /// for a specific person. They take precedence over [Self::overrides] and the mapping table,
+    /// and don't count as unknown.
+    pub fn preserve(mut self, chars: &[char]) -> Self {
+        self.preserve.extend_from_slice(chars);
+        self
+    }
+
+    /// Phrases `(from, to)` that are replaced before the per char conversion. At every position the
+    /// longest matching phrase wins. The replacement is not converted further. If a phrase is
+    /// added multiple times, the last replacement wins.
+    ///
+    /// This allows e.g. to map a japanese compound to a specific chinese compound.
+    pub fn phrases(mut self, phrases: &[(&str, &str)]) -> Self {
+        self.phrases.extend(
+            phrases
+                .iter()
+                .filter(|(from, _)| !from.is_empty())
+                .map(|(from, to)| (from.to_string(), to.to_string())),
+        );
+        self
+    }
+
+    /// Number of inputs, whose result is cached by [Converter::convert_cached]. The default is 0,
+    /// which disables the cache.
+    #[cfg(feature = "lru")]
+    pub fn cache_capacity(mut self, cache_capacity: usize) -> Self {
+        self.cache_capacity = cache_capacity;
+        self
+    }
+
+    pub fn build(mut self) -> Converter {
+        // Stable sort, so the last override of a char ends up last
+        self.overrides.sort_by_key(|(from, _)| *from);
+        self.overrides.reverse();
+        self.overrides.dedup_by_key(|(from, _)| *from);
+        self.overrides.reverse();
+        self.preserve.sort_unstable();
+        self.preserve.dedup();
+        let mut phrases: FnvHashMap<char, Vec<(String, String)>> = FnvHashMap::default();
+        for (from, to) in self.phrases {
+            let first = from.chars().next().expect("empty phrases are filtered");
+            let bucket = phrases.entry(first).or_default();
+            bucket.retain(|(existing, _)| *existing != from);
+            bucket.push((from, to));
+        }
+        for bucket in phrases.values_mut() {
+            bucket.sort_by_key(|(from, _)| core::cmp::Reverse(from.len()));
+        }
+        // The embedded table only borrows static data, so cloning it is cheap
+        let table = self
+            .table
+            .unwrap_or_else(|| Arc::new(get_mapping_table().clone()));
+        Converter {
+            table,
+            target: self.target,
+            on_unknown: self.on_unknown,
+            on_kokuji: self.on_kokuji,
+            normalize_width: self.normalize_width,
+            widen_katakana: self.widen_katakana,
+            normalize_daiji: self.normalize_daiji,
+            conservative: self.conservative,
+            skip_non_cjk: self.skip_non_cjk,
+            strip_variation_selectors: self.strip_variation_selectors,
+            furigana: self.furigana,
+            expand_iteration_mark: self.expand_iteration_mark,
+            #[cfg(feature = "compat-ideographs")]
+            normalize_compatibility: self.normalize_compatibility,
+            overrides: self.overrides,
+            preserve: self.preserve,
+            phrases,
+            #[cfg(feature = "lru")]
+            cache: Arc::new(Mutex::new(LruCache::new(self.cache_capacity))),
+        }
+    }
+}
+
+/// A configured conversion, see [ConverterBuilder]. It can be reused across calls.
+///
+/// It owns its mapping table, and is `Send + Sync`, so it can be shared between threads, e.g. in
+/// an `Arc`.
+#[derive(Debug, Clone)]
+pub struct Converter {
+    table: Arc<MappingTable>,
+    target: TargetScript,
+    on_unknown: UnknownAction,
+    on_kokuji: Option<KokujiAction>,
+    normalize_width: bool,
+    widen_katakana: bool,
+    normalize_daiji: bool,
+    conservative: bool,
+    skip_non_cjk: bool,
+    strip_variation_selectors: bool,
+    furigana: Furigana,
+    expand_iteration_mark: bool,
+    #[cfg(feature = "compat-ideographs")]
+    normalize_compatibility: bool,
+    /// Sorted by the overridden char, without duplicates.
+    overrides: Vec<(char, char)>,
+    /// Sorted, without duplicates.
+    preserve: Vec<char>,
+    /// Phrases by their first char, longest first.
+    phrases: FnvHashMap<char, Vec<(String, String)>>,
+    /// Shared between clones of the converter.
+    #[cfg(feature = "lru")]
+    cache: Arc<Mutex<LruCache>>,
+}
+
+impl Converter {
+    pub fn builder() -> ConverterBuilder {
+        ConverterBuilder::new()
+    }
+
+    /// A converter with the embedded mapping table and the default options of [ConverterBuilder].
+    pub fn builtin() -> Converter {
+        ConverterBuilder::new().build()
+    }
+
+    /// The mapping table of the converter.
+    pub fn table(&self) -> &MappingTable {
+        &self.table
+    }
+
+    /// A converter to the target script, with mappings that take precedence over the mapping
+    /// table, see [ConverterBuilder::overrides].
+    ///
+    /// ```
+    /// # #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+    /// # {
+    /// use kanji_hanzi_converter::{Converter, TargetScript};
+    ///
+    /// let converter = Converter::with_overrides(TargetScript::Simplified, &[('醫', '醫')]);
+    /// assert_eq!(converter.convert("醫學"), "醫学");
+    /// # }
+    /// ```
+    pub fn with_overrides(target: TargetScript, overrides: &[(char, char)]) -> Converter {
+        ConverterBuilder::new()
+            .target(target)
+            .overrides(overrides)
+            .build()
+    }
+
+    /// Converts a single char. Returns `None` if the char is skipped.
+    pub fn convert_char(&self, cha: char) -> Option<char> {
+        match self.convert_char_to_output(cha)? {
+            Output::Char(converted) => Some(converted),
+            Output::Str(_) => Some(cha),
+        }
+    }
+
+    fn convert_char_to_output(&self, cha: char) -> Option<Output<'_>> {
+        #[cfg(feature = "compat-ideographs")]
+        let cha = if self.normalize_compatibility {
+            crate::normalize_compatibility_ideograph(cha)
+        } else {
+            cha
+        };
+        if is_variation_selector(cha) {
+            return (!self.strip_variation_selectors).then_some(Output::Char(cha));
+        }
+        if self.skip_non_cjk && !is_cjk_ideograph(cha) {
+            return Some(Output::Char(cha));
+        }
+        if self.preserve.binary_search(&cha).is_ok() {
+            return Some(Output::Char(cha));
+        }
+        if let Ok(pos) = self.overrides.binary_search_by_key(&cha, |(from, _)| *from) {
+            return Some(Output::Char(self.overrides[pos].1));
+        }
+        let daiji = daiji_numeral(cha, self.target).filter(|_| self.normalize_daiji);
+        if let Some(numeral) = daiji {
+            let converted = self.table.convert_char(numeral, self.target);
+            return Some(Output::Char(converted.unwrap_or(numeral)));
+        }
+        if let Some(converted) = self.table.convert_char(cha, self.target) {
+            if self.conservative && self.candidate_count(cha) > 1 {
+                return Some(Output::Char(cha));
+            }
+            return Some(Output::Char(converted));
+        }
+        if let Some(on_kokuji) = &self.on_kokuji {
+            if is_known_kanji(cha) {
+                return match on_kokuji {
+                    KokujiAction::Keep => Some(Output::Char(cha)),
+                    KokujiAction::Drop => None,
+                    KokujiAction::Replace(replacement) => Some(Output::Str(replacement)),
+                };
+            }
+        }
+        if self.widen_katakana {
+            let widened = to_full_width_katakana(cha);
+            if widened != cha {
+                return Some(Output::Char(widened));
+            }
+        }
+        if self.normalize_width {
+            let normalized = to_half_width(cha);
+            if normalized != cha {
+                return Some(Output::Char(normalized));
+            }
+        }
+        match self.on_unknown {
+            UnknownAction::Keep => Some(Output::Char(cha)),
+            UnknownAction::Replace(replacement) => Some(Output::Char(replacement)),
+            UnknownAction::Skip => None,
+        }
+    }
+
+    pub fn convert(&self, input: &str) -> String {
+        let replaces_kokuji = matches!(self.on_kokuji, Some(KokujiAction::Replace(_)));
+        if self.furigana == Furigana::Convert
+            && self.phrases.is_empty()
+            && !replaces_kokuji
+            && !self.expand_iteration_mark
+            && !self.widen_katakana
+        {
+            return input
+                .chars()
+                .filter_map(|cha| self.convert_char(cha))
+                .collect();
+        }
+        let mut out = String::with_capacity(input.len());
+        let mut rest = input;
+        let mut after_ideograph = false;
+        // The converted form of the previous char, if it is a kanji
+        let mut previous_kanji = None;
+        while let Some(cha) = rest.chars().next() {
+            if let Some((from, to)) = self.matching_phrase(cha, rest) {
+                out.push_str(to);
+                rest = &rest[from.len()..];
+                after_ideograph = from.chars().next_back().is_some_and(is_cjk_ideograph);
+                previous_kanji = None;
+                continue;
+            }
+            if self.widen_katakana && !self.skip_non_cjk {
+                let mark = rest[cha.len_utf8()..].chars().next();
+                if let Some(combined) = mark.and_then(|mark| with_sound_mark(cha, mark)) {
+                    out.push(combined);
+                    rest = &rest[cha.len_utf8() + mark.map_or(0, char::len_utf8)..];
+                    after_ideograph = false;
+                    previous_kanji = None;
+                    continue;
+                }
+            }
+            if cha == ITERATION_MARK && self.expand_iteration_mark {
+                if let Some(kanji) = previous_kanji {
+                    out.push(kanji);
+                    rest = &rest[cha.len_utf8()..];
+                    continue;
+                }
+            }
+            if after_ideograph && self.furigana != Furigana::Convert {
+                if let Some(len) = reading_len(rest) {
+                    if self.furigana == Furigana::Keep {
+                        out.push_str(&rest[..len]);
+                    }
+                    rest = &rest[len..];
+                    after_ideograph = false;
+                    previous_kanji = None;
+                    continue;
+                }
+            }
+            previous_kanji = None;
+            match self.convert_char_to_output(cha) {
+                Some(Output::Char(converted)) => {
+                    out.push(converted);
+                    if is_cjk_ideograph(cha) {
+                        previous_kanji = Some(converted);
+                    }
+                }
+                Some(Output::Str(replacement)) => out.push_str(replacement),
+                None => {}
+            }
+            after_ideograph = is_cjk_ideograph(cha);
+            rest = &rest[cha.len_utf8()..];
+        }
+        out
+    }
+
+    /// Number of candidates of the entry used to convert the char to the target script.
+    fn candidate_count(&self, cha: char) -> usize {
+        match self.target {
+            #[cfg(feature = "direction-japanese")]
+            TargetScript::Japanese => {
+                usize::from(self.table.convert_char_to_japanese(cha).is_some())
+            }
+            #[cfg(feature = "direction-traditional")]
+            TargetScript::Traditional => self.table.all_traditional_candidates(cha).len(),
+            #[cfg(feature = "direction-simplified")]
+            TargetScript::Simplified => self.table.all_simplified_candidates(cha).len(),
+        }
+    }
+
+    /// The longest phrase at the start of `text`, which starts with `first`.
+    fn matching_phrase(&self, first: char, text: &str) -> Option<&(String, String)> {
+        self.phrases
+            .get(&first)?
+            .iter()
+            .find(|(from, _)| text.starts_with(from.as_str()))
+    }
+
+    /// Like [Self::convert], but repeated inputs are served from a cache of the least recently
+    /// used inputs. The capacity is set with [ConverterBuilder::cache_capacity]. This only pays
+    /// off if the same inputs are converted repeatedly.
+    #[cfg(feature = "lru")]
+    pub fn convert_cached(&self, input: &str) -> String {
+        let mut cache = self.cache.lock().unwrap_or_else(|err| err.into_inner());
+        if let Some(converted) = cache.get(input) {
+            return converted;
+        }
+        let converted = self.convert(input);
+        cache.insert(input, converted.clone());
+        converted
+    }
+}
+
+/// 々, which repeats the preceding kanji.
+const ITERATION_MARK: char = '\u{3005}';
+
+/// The conversion of a single char.
+enum Output<'a> {
+    Char(char),
+    Str(&'a str),
+}
+
+/// Parses a mapping table like [load_mapping_from_str], and creates a converter with it, with the
+/// default options of [ConverterBuilder].
+///
+/// ```
+/// # #[cfg(feature = "direction-simplified")]
+/// # {
+/// use kanji_hanzi_converter::Converter;
+///
+/// let converter: Converter = "学\t學\t学".parse().unwrap();
+/// assert_eq!(converter.convert("學校"), "学校");
+/// # }
+/// ```
+impl FromStr for Converter {
+    type Err = ParseError;
+
+    fn from_str(data: &str) -> Result<Self, Self::Err> {
+        let table = load_mapping_from_str(data)?;
+        Ok(ConverterBuilder::new().table(table).build())
+    }
+}
+
+/// The byte length of a reading in parentheses at the start of `text`, like `(かんじ)`.
+fn reading_len(text: &str) -> Option<usize> {
+    let close = match text.chars().next()? {
+        '(' => ')',
+        '（' => '）',
+        _ => return None,
+    };
+    let open_len = text.chars().next()?.len_utf8();
+    let reading_end = open_len + text[open_len..].find(close)?;
+    let reading = &text[open_len..reading_end];
+    if reading.is_empty() || !reading.chars().all(is_kana) {
+        return None;
+    }
+    Some(reading_end + close.len_utf8())
+}
+
+/// The common numeral of a daiji, in its japanese form. 萬 is the common form in Traditional
+/// Chinese, so it is only a daiji for the other targets.
+#[cfg_attr(not(feature = "direction-traditional"), allow(unused_variables))]
+fn daiji_numeral(cha: char, target: TargetScript) -> Option<char> {
+    let numeral = match cha {
+        '壱' | '壹' => '一',
+        '弐' | '貳' | '貮' | '贰' => '二',
+        '参' | '參' | '叁' => '三',
+        '肆' => '四',
+        '伍' => '五',
+        '陸' => '六',
+        '漆' | '柒' => '七',
+        '捌' => '八',
+        '玖' => '九',
+        '拾' => '十',
+        '佰' | '陌' => '百',
+        '阡' | '仟' => '千',
+        #[cfg(feature = "direction-traditional")]
+        '萬' if target == TargetScript::Traditional => return None,
+        '萬' => '万',
+        _ => return None,
+    };
+    Some(numeral)
+}
+
+fn is_variation_selector(cha: char) -> bool {
+    matches!(cha, '\u{FE00}'..='\u{FE0F}' | '\u{E0100}'..='\u{E01EF}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "direction-simplified")]
+    use crate::convert_char_to_simplified;
+
+    #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+    #[test]
+    fn default_converter() {
+        let converter = Converter::builder().build();
+        assert_eq!(converter.convert("醫生a１"), "医生a１");
+    }
+
+    #[cfg(feature = "direction-japanese")]
+    #[test]
+    fn unknown_action() {
+        let builder = ConverterBuilder::new().target(TargetScript::Japanese);
+        let replace = builder
+            .clone()
+            .on_unknown(UnknownAction::Replace('?'))
+            .build();
+        assert_eq!(replace.convert("學a"), "学?");
+        let skip = builder.clone().on_unknown(UnknownAction::Skip).build();
+        assert_eq!(skip.convert("學a"), "学");
+        let skip = builder
+            .on_unknown(UnknownAction::Skip)
+            .normalize_width(true)
+            .build();
+        assert_eq!(skip.convert("學ａa"), "学a");
+    }
+
+    #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+    #[test]
+    fn on_kokuji() {
+        let text = "峠の茶屋a";
+        let builder = ConverterBuilder::new().on_unknown(UnknownAction::Replace('?'));
+        assert_eq!(builder.clone().build().convert(text), "??茶屋?");
+        let keep = builder.clone().on_kokuji(KokujiAction::Keep).build();
+        assert_eq!(keep.convert(text), "峠?茶屋?");
+        let drop = builder.clone().on_kokuji(KokujiAction::Drop).build();
+        assert_eq!(drop.convert(text), "?茶屋?");
+        let replace = builder
+            .on_kokuji(KokujiAction::Replace("[toge]".to_string()))
+            .build();
+        assert_eq!(replace.convert(text), "[toge]?茶屋?");
+        assert_eq!(replace.convert_char('峠'), Some('峠'));
+    }
+
+    #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+    #[test]
+    fn iteration_mark() {
+        let text = "時々、人々々と々";
+        assert_eq!(
+            ConverterBuilder::new().build().convert(text),
+            "时々、人々々と々"
+        );
+        let expand = ConverterBuilder::new().expand_iteration_mark(true).build();
+        assert_eq!(expand.convert(text), "时时、人人人と々");
+        let traditional = ConverterBuilder::new()
+            .target(TargetScript::Traditional)
+            .expand_iteration_mark(true)
+            .build();
+        assert_eq!(traditional.convert("時々"), "時時");
+    }
+
+    #[cfg(feature = "direction-japanese")]
+    #[test]
+    fn widen_katakana() {
+        let text = "ｶﾞｯｺｳﾍﾟﾝ學ﾞ";
+        let builder = ConverterBuilder::new()
+            .target(TargetScript::Japanese)
+            .on_unknown(UnknownAction::Replace('?'));
+        assert_eq!(builder.clone().build().convert(text), "????????学?");
+        let widen = builder.widen_katakana(true).build();
+        assert_eq!(widen.convert(text), "ガッコウペン学゛");
+        assert_eq!(widen.convert_char('ｶ'), Some('カ'));
+    }
+
+    #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+    #[test]
+    fn normalize_daiji() {
+        let text = "金壹貳參肆伍萬圓";
+        assert_eq!(
+            ConverterBuilder::new().build().convert(text),
+            "金壹贰参肆伍万圆"
+        );
+        // 萬 is the traditional form of 万, so only Traditional Chinese keeps it
+        let plain = ConverterBuilder::new().target(TargetScript::Traditional);
+        assert_eq!(plain.build().convert("萬圓"), "萬圓");
+        assert_eq!(ConverterBuilder::new().build().convert("萬"), "万");
+        let normalize = ConverterBuilder::new().normalize_daiji(true);
+        assert_eq!(normalize.clone().build().convert(text), "金一二三四五万圆");
+        let traditional = normalize.target(TargetScript::Traditional).build();
+        assert_eq!(traditional.convert(text), "金一二三四五萬圓");
+        assert_eq!(
+            traditional.convert("壱弐参陸漆捌玖拾佰阡"),
+            "一二三六七八九十百千"
+        );
+    }
+
+    #[cfg(feature = "direction-simplified")]
+    #[test]
+    fn overrides() {
+        assert_eq!(convert_char_to_simplified('一'), Some('一'));
+        let converter = Converter::with_overrides(TargetScript::Simplified, &[('一', '壹')]);
+        assert_eq!(converter.convert("壹一"), "壹壹");
+        let converter = ConverterBuilder::new()
+            .overrides(&[('醫', 'x'), ('學', '斈')])
+            .overrides(&[('醫', '医')])
+            .build();
+        assert_eq!(converter.convert("醫學生"), "医斈生");
+    }
+
+    #[cfg(feature = "direction-traditional")]
+    #[test]
+    fn preserve() {
+        let converter = ConverterBuilder::new()
+            .target(TargetScript::Traditional)
+            .on_unknown(UnknownAction::Replace('?'))
+            .overrides(&[('恵', 'x')])
+            .preserve(&['恵', '国'])
+            .preserve(&['恵'])
+            .build();
+        assert_eq!(converter.convert("恵と国学"), "恵?国學");
+        assert_eq!(
+            ConverterBuilder::new()
+                .target(TargetScript::Traditional)
+                .build()
+                .convert("恵と国学"),
+            "惠と國學"
+        );
+    }
+
+    #[cfg(feature = "direction-traditional")]
+    #[test]
+    fn conservative() {
+        let builder = ConverterBuilder::new().target(TargetScript::Traditional);
+        assert_eq!(builder.clone().build().convert("医学"), "醫學");
+        let conservative = builder.conservative(true).build();
+        assert_eq!(conservative.convert("医学"), "医學");
+        let overridden = ConverterBuilder::new()
+            .target(TargetScript::Traditional)
+            .conservative(true)
+            .overrides(&[('医', '醫')])
+            .build();
+        assert_eq!(overridden.convert("医学"), "醫學");
+    }
+
+    #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+    #[test]
+    fn skip_non_cjk() {
+        let text = "「學校」へ、ａ醫";
+        let all = ConverterBuilder::new()
+            .on_unknown(UnknownAction::Skip)
+            .normalize_width(true);
+        assert_eq!(all.clone().build().convert(text), "学校a医");
+        let cjk_only = all.skip_non_cjk(true).build();
+        assert_eq!(cjk_only.convert(text), "「学校」へ、ａ医");
+    }
+
+    #[cfg(feature = "direction-japanese")]
+    #[test]
+    fn variation_selectors() {
+        // 學 followed by VS17
+        let text = "學\u{E0100}校";
+        assert_eq!(crate::convert_to_japanese_kanji(text), "学\u{E0100}校");
+        let builder = ConverterBuilder::new()
+            .target(TargetScript::Japanese)
+            .on_unknown(UnknownAction::Replace('?'));
+        assert_eq!(builder.clone().build().convert(text), "学\u{E0100}校");
+        let strip = builder.strip_variation_selectors(true).build();
+        assert_eq!(strip.convert(text), "学校");
+    }
+
+    #[cfg(all(
+        feature = "lru",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn convert_cached() {
+        let converter = ConverterBuilder::new().cache_capacity(2).build();
+        for _ in 0..3 {
+            assert_eq!(converter.convert_cached("醫生"), "医生");
+            assert_eq!(converter.convert_cached("學校"), "学校");
+            assert_eq!(converter.convert_cached("abc"), "abc");
+        }
+        let uncached = ConverterBuilder::new().build();
+        assert_eq!(uncached.convert_cached("醫生"), "医生");
+    }
+
+    #[cfg(all(
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn furigana() {
+        let text = "學校(がっこう)と醫者（いしゃ）(a)";
+        let builder = ConverterBuilder::new()
+            .target(TargetScript::Japanese)
+            .on_unknown(UnknownAction::Replace('?'));
+        assert_eq!(
+            builder.clone().build().convert(text),
+            "学校???????医者????????"
+        );
+        let keep = builder.clone().furigana(Furigana::Keep).build();
+        assert_eq!(keep.convert(text), "学校(がっこう)?医者（いしゃ）???");
+        let strip = builder.furigana(Furigana::Strip).build();
+        assert_eq!(strip.convert(text), "学校?医者???");
+        // Unclosed or not after kanji
+        let strip = ConverterBuilder::new().furigana(Furigana::Strip).build();
+        assert_eq!(strip.convert("(がっこう)學(がっ"), "(がっこう)学(がっ");
+    }
+
+    #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+    #[test]
+    fn phrases() {
+        let converter = ConverterBuilder::new()
+            .target(TargetScript::Simplified)
+            .phrases(&[("手紙", "信"), ("手", "x"), ("手紙入", "y")])
+            .phrases(&[("手", "手")])
+            .build();
+        assert_eq!(converter.convert("手紙を手に學"), "信を手に学");
+        assert_eq!(converter.convert("手紙入れ"), "yれ");
+        assert_eq!(converter.convert("紙"), "纸");
+    }
+
+    #[cfg(all(
+        feature = "compat-ideographs",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn normalize_compatibility() {
+        let text = "音\u{F914}";
+        assert_eq!(ConverterBuilder::new().build().convert(text), text);
+        let converter = ConverterBuilder::new()
+            .normalize_compatibility(true)
+            .build();
+        assert_eq!(converter.convert(text), "音乐");
+    }
+
+    #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+    #[test]
+    fn shared_between_threads() {
+        let converter: Converter = "医\t醫\t医\n学\t學\t学".parse().unwrap();
+        let converter = Arc::new(converter);
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let converter = Arc::clone(&converter);
+                std::thread::spawn(move || converter.convert("醫學生"))
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), "医学生");
+        }
+        // 書 is only in the embedded table
+        assert_eq!(Converter::builtin().convert("書"), "书");
+        assert_eq!(converter.convert("書"), "書");
+    }
+}
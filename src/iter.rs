@@ -0,0 +1,249 @@
+use alloc::vec::Vec;
+
+#[cfg(feature = "direction-japanese")]
+use crate::convert_char_to_japanese;
+#[cfg(feature = "direction-simplified")]
+use crate::convert_char_to_simplified;
+#[cfg(feature = "direction-traditional")]
+use crate::convert_char_to_traditional;
+
+/// Iterator that lazily converts chars, see [convert_chars_to_simplified].
+///
+/// It is `Clone` if the underlying iterator is `Clone`.
+#[derive(Debug, Clone)]
+pub struct ConvertChars<I> {
+    iter: I,
+    convert_char: fn(char) -> Option<char>,
+}
+
+impl<I: Iterator<Item = char>> Iterator for ConvertChars<I> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        let cha = self.iter.next()?;
+        Some((self.convert_char)(cha).unwrap_or(cha))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I: DoubleEndedIterator<Item = char>> DoubleEndedIterator for ConvertChars<I> {
+    fn next_back(&mut self) -> Option<char> {
+        let cha = self.iter.next_back()?;
+        Some((self.convert_char)(cha).unwrap_or(cha))
+    }
+}
+
+/// Lazily converts chars to Simplified Chinese.
+/// Leaves chars unchanged that can't be converted.
+#[cfg(feature = "direction-simplified")]
+pub fn convert_chars_to_simplified<I: Iterator<Item = char>>(iter: I) -> ConvertChars<I> {
+    ConvertChars {
+        iter,
+        convert_char: convert_char_to_simplified,
+    }
+}
+
+/// Lazily converts chars to Traditional Chinese.
+/// Leaves chars unchanged that can't be converted.
+#[cfg(feature = "direction-traditional")]
+pub fn convert_chars_to_traditional<I: Iterator<Item = char>>(iter: I) -> ConvertChars<I> {
+    ConvertChars {
+        iter,
+        convert_char: convert_char_to_traditional,
+    }
+}
+
+/// Lazily converts chars to Japanese Kanji.
+/// Leaves chars unchanged that can't be converted.
+#[cfg(feature = "direction-japanese")]
+pub fn convert_chars_to_japanese<I: Iterator<Item = char>>(iter: I) -> ConvertChars<I> {
+    ConvertChars {
+        iter,
+        convert_char: convert_char_to_japanese,
+    }
+}
+
+/// Lazily converts to Simplified Chinese, yielding the byte offset of every source char in the
+/// input together with the converted char.
+#[cfg(feature = "direction-simplified")]
+pub fn convert_indexed_to_simplified(input: &str) -> impl Iterator<Item = (usize, char)> + '_ {
+    convert_indexed(input, convert_char_to_simplified)
+}
+
+/// Lazily converts to Traditional Chinese, yielding the byte offset of every source char in the
+/// input together with the converted char.
+#[cfg(feature = "direction-traditional")]
+pub fn convert_indexed_to_traditional(input: &str) -> impl Iterator<Item = (usize, char)> + '_ {
+    convert_indexed(input, convert_char_to_traditional)
+}
+
+/// Lazily converts to Japanese Kanji, yielding the byte offset of every source char in the input
+/// together with the converted char.
+#[cfg(feature = "direction-japanese")]
+pub fn convert_indexed_to_japanese(input: &str) -> impl Iterator<Item = (usize, char)> + '_ {
+    convert_indexed(input, convert_char_to_japanese)
+}
+
+fn convert_indexed(
+    input: &str,
+    convert_char: fn(char) -> Option<char>,
+) -> impl Iterator<Item = (usize, char)> + '_ {
+    input
+        .char_indices()
+        .map(move |(pos, cha)| (pos, convert_char(cha).unwrap_or(cha)))
+}
+
+/// Converts the chars to Simplified Chinese in place, without allocating.
+/// Leaves chars unchanged that can't be converted.
+#[cfg(feature = "direction-simplified")]
+pub fn convert_chars_in_place_to_simplified(buf: &mut [char]) {
+    convert_in_place(buf, convert_char_to_simplified);
+}
+
+/// Converts the chars to Traditional Chinese in place, without allocating.
+/// Leaves chars unchanged that can't be converted.
+#[cfg(feature = "direction-traditional")]
+pub fn convert_chars_in_place_to_traditional(buf: &mut [char]) {
+    convert_in_place(buf, convert_char_to_traditional);
+}
+
+/// Converts the chars to Japanese Kanji in place, without allocating.
+/// Leaves chars unchanged that can't be converted.
+#[cfg(feature = "direction-japanese")]
+pub fn convert_chars_in_place_to_japanese(buf: &mut [char]) {
+    convert_in_place(buf, convert_char_to_japanese);
+}
+
+fn convert_in_place(buf: &mut [char], convert_char: fn(char) -> Option<char>) {
+    for cha in buf {
+        *cha = convert_char(*cha).unwrap_or(*cha);
+    }
+}
+
+/// Converts the chars to Simplified Chinese, without UTF-8 decoding, for input that is already
+/// split into chars. See [convert_chars_in_place_to_simplified] to avoid the allocation.
+/// Leaves chars unchanged that can't be converted.
+#[cfg(feature = "direction-simplified")]
+pub fn convert_slice_to_simplified(chars: &[char]) -> Vec<char> {
+    convert_slice(chars, convert_char_to_simplified)
+}
+
+/// Converts the chars to Traditional Chinese, without UTF-8 decoding, for input that is already
+/// split into chars. See [convert_chars_in_place_to_traditional] to avoid the allocation.
+/// Leaves chars unchanged that can't be converted.
+#[cfg(feature = "direction-traditional")]
+pub fn convert_slice_to_traditional(chars: &[char]) -> Vec<char> {
+    convert_slice(chars, convert_char_to_traditional)
+}
+
+/// Converts the chars to Japanese Kanji, without UTF-8 decoding, for input that is already split
+/// into chars. See [convert_chars_in_place_to_japanese] to avoid the allocation.
+/// Leaves chars unchanged that can't be converted.
+#[cfg(feature = "direction-japanese")]
+pub fn convert_slice_to_japanese(chars: &[char]) -> Vec<char> {
+    convert_slice(chars, convert_char_to_japanese)
+}
+
+fn convert_slice(chars: &[char], convert_char: fn(char) -> Option<char>) -> Vec<char> {
+    chars
+        .iter()
+        .map(|cha| convert_char(*cha).unwrap_or(*cha))
+        .collect()
+}
+
+#[cfg(all(
+    test,
+    feature = "direction-simplified",
+    feature = "direction-traditional"
+))]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+
+    #[cfg(all(
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn convert_chars() {
+        let iter = convert_chars_to_simplified("醫生a".chars());
+        assert_eq!(iter.clone().collect::<String>(), "医生a");
+        assert_eq!(iter.rev().collect::<String>(), "a生医");
+        assert_eq!(
+            convert_chars_to_traditional("学".chars()).collect::<String>(),
+            "學"
+        );
+        assert_eq!(
+            convert_chars_to_japanese("學".chars()).collect::<String>(),
+            "学"
+        );
+    }
+
+    #[test]
+    fn convert_chars_is_lazy() {
+        let polled = Cell::new(0);
+        let mut iter = convert_chars_to_simplified("醫生".chars().inspect(|_| {
+            polled.set(polled.get() + 1);
+        }));
+        assert_eq!(polled.get(), 0);
+        assert_eq!(iter.next(), Some('医'));
+        assert_eq!(polled.get(), 1);
+    }
+
+    #[cfg(all(
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn convert_in_place() {
+        let mut buf: Vec<char> = "醫生a學".chars().collect();
+        convert_chars_in_place_to_simplified(&mut buf);
+        assert_eq!(buf, ['医', '生', 'a', '学']);
+        convert_chars_in_place_to_traditional(&mut buf);
+        assert_eq!(buf, ['醫', '生', 'a', '學']);
+        convert_chars_in_place_to_japanese(&mut buf);
+        assert_eq!(buf, ['医', '生', 'a', '学']);
+    }
+
+    #[cfg(all(
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn convert_slice() {
+        let text = "醫生は學校へa𠮟";
+        let chars: Vec<char> = text.chars().collect();
+        let simplified: String = convert_slice_to_simplified(&chars).into_iter().collect();
+        assert_eq!(simplified, crate::convert_to_simplified_chinese(text));
+        let traditional: String = convert_slice_to_traditional(&chars).into_iter().collect();
+        assert_eq!(traditional, crate::convert_to_traditional_chinese(text));
+        let japanese: String = convert_slice_to_japanese(&chars).into_iter().collect();
+        assert_eq!(japanese, crate::convert_to_japanese_kanji(text));
+        assert!(convert_slice_to_simplified(&[]).is_empty());
+    }
+
+    #[cfg(all(
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn convert_indexed() {
+        let converted: Vec<(usize, char)> = convert_indexed_to_simplified("a醫𠮟生").collect();
+        assert_eq!(converted, [(0, 'a'), (1, '医'), (4, '𠮟'), (8, '生')]);
+        let mut iter = convert_indexed_to_traditional("学");
+        assert_eq!(iter.next(), Some((0, '學')));
+        assert_eq!(iter.next(), None);
+        assert_eq!(
+            convert_indexed_to_japanese("學醫").collect::<Vec<_>>(),
+            [(0, '学'), (3, '医')]
+        );
+    }
+}
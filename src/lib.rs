@@ -7,181 +7,2932 @@
 //! Another Example for ambiguity are those entries from the underlying dataset.
 //! The correct entry is typically line 5055, the line 3365 is strange.
 //! Line    Japanese Traditional Simplified
-//! 3365    學	     學	         学
-//! 5055    学	     學	         学
-//! 5383    斈	     學	         学
+//! 3365    學       學          学
+//! 5055    学       學          学
+//! 5383    斈       學          学
 //!
 //! For that reason, only japanese characters that are also in the kanji list (2310 characters) are considered.
+//! So 學 resolves through line 5055 and converts to 学.
+//!
+//! If multiple entries still claim the same char, the resolution is deterministic: a japanese
+//! kanji always resolves to its own entry, and a first chinese candidate to the first entry in
+//! file order. Further candidates resolve through the entry where they have the lowest position,
+//! unless they have an entry of their own, e.g. 體 resolves to 体, as its own line is not in the
+//! kanji list. See [MappingTable::from_entries]. To prefer a specific entry in a custom table,
+//! skip the others with [MappingTable::retain].
+//!
+//! # Unicode normalization
+//!
+//! The input doesn't need to be normalized. CJK Unified Ideographs have no canonical
+//! decomposition, so they are single chars in both NFC and NFD and are converted the same way.
+//! Decomposed sequences, e.g. a kana with a combining dakuten, are passed through unchanged.
+//!
+//! # Ideographic zero
+//!
+//! 〇 (U+3007), the zero of dates and numbers like 二〇二四, is the same char in all three scripts.
+//! It is not in the mapping table, so it is passed through unchanged. Note that a [Converter] with
+//! [UnknownAction::Replace] or [UnknownAction::Skip] treats it as unknown char.
+//!
+//! # no_std
+//!
+//! The crate is `no_std` compatible with `alloc`, by disabling the default `std` feature. The
+//! lazily initialized statics then require a [critical-section](https://docs.rs/critical-section)
+//! implementation for the target.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+// Without any direction only the lookups are left, and the shared conversion code is unused
+#![cfg_attr(
+    not(any(
+        feature = "direction-simplified",
+        feature = "direction-traditional",
+        feature = "direction-japanese"
+    )),
+    allow(dead_code, unused_imports, unused_variables, unreachable_code)
+)]
+
+extern crate alloc;
+
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+use core::str::FromStr;
+
+#[cfg(feature = "std")]
 use fnv::{FnvHashMap, FnvHashSet};
 use once_cell::sync::OnceCell;
-fn _get_hashmap() -> FnvHashMap<char, Entry> {
-    let mapping = include_str!("../kanji_mapping_table.txt");
 
-    let mut hashmap = FnvHashMap::default();
+// Without std the fnv aliases are not available, hashbrown provides the same collections
+#[cfg(not(feature = "std"))]
+type FnvHashMap<K, V> = hashbrown::HashMap<K, V, fnv::FnvBuildHasher>;
+#[cfg(not(feature = "std"))]
+type FnvHashSet<T> = hashbrown::HashSet<T, fnv::FnvBuildHasher>;
+
+#[cfg(feature = "capi")]
+pub mod capi;
+mod char_map;
+#[cfg(any(
+    feature = "direction-simplified",
+    feature = "direction-traditional",
+    feature = "direction-japanese"
+))]
+mod converter;
+mod iter;
+#[cfg(feature = "lru")]
+mod lru;
+mod mapping_table;
+#[cfg(feature = "direction-traditional")]
+mod region;
+#[cfg(feature = "std")]
+mod stream;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use char_map::CharMap;
+#[cfg(any(
+    feature = "direction-simplified",
+    feature = "direction-traditional",
+    feature = "direction-japanese"
+))]
+pub use converter::{Converter, ConverterBuilder, Furigana, KokujiAction, UnknownAction};
+pub use iter::ConvertChars;
+#[cfg(feature = "direction-japanese")]
+pub use iter::{
+    convert_chars_in_place_to_japanese, convert_chars_to_japanese, convert_indexed_to_japanese,
+    convert_slice_to_japanese,
+};
+#[cfg(feature = "direction-simplified")]
+pub use iter::{
+    convert_chars_in_place_to_simplified, convert_chars_to_simplified,
+    convert_indexed_to_simplified, convert_slice_to_simplified,
+};
+#[cfg(feature = "direction-traditional")]
+pub use iter::{
+    convert_chars_in_place_to_traditional, convert_chars_to_traditional,
+    convert_indexed_to_traditional, convert_slice_to_traditional,
+};
+#[cfg(feature = "std")]
+pub use mapping_table::{load_mapping_from_path, LoadError};
+pub use mapping_table::{load_mapping_from_str, MappingStats, MappingTable, ParseError};
+#[cfg(feature = "direction-traditional")]
+pub use region::{
+    convert_char_to_traditional_region, convert_to_traditional_chinese_region, Region,
+};
+#[cfg(all(feature = "tokio", feature = "direction-japanese"))]
+pub use stream::convert_async_to_japanese;
+#[cfg(all(feature = "tokio", feature = "direction-simplified"))]
+pub use stream::convert_async_to_simplified;
+#[cfg(all(feature = "tokio", feature = "direction-traditional"))]
+pub use stream::convert_async_to_traditional;
+#[cfg(all(feature = "std", feature = "direction-japanese"))]
+pub use stream::convert_stream_to_japanese;
+#[cfg(all(feature = "std", feature = "direction-simplified"))]
+pub use stream::convert_stream_to_simplified;
+#[cfg(all(feature = "std", feature = "direction-traditional"))]
+pub use stream::convert_stream_to_traditional;
+
+mod generated {
+    use super::*;
+
+    include!(concat!(env!("OUT_DIR"), "/mapping_table.rs"));
+
+    /// The mapping table embedded in the crate, generated by the build script.
+    pub(crate) static MAPPING_TABLE: MappingTable =
+        MappingTable::from_static(ENTRIES, JAPANESE_KEYS, SIMPLIFIED_KEYS, TRADITIONAL_KEYS);
+}
+
+/// The mapping table embedded in the crate.
+fn get_mapping_table() -> &'static MappingTable {
+    &generated::MAPPING_TABLE
+}
+
+/// Entries keyed by their japanese kanji.
+///
+/// The map is generated at compile time, so there's no initialization cost on first use.
+pub fn get_hashmap() -> CharMap<'static> {
+    get_mapping_table().japanese_map()
+}
+
+/// Entries keyed by their simplified chinese form only.
+fn get_simplified_hashmap() -> CharMap<'static> {
+    get_mapping_table().simplified_map()
+}
+
+/// Entries keyed by their traditional chinese form only.
+fn get_traditional_hashmap() -> CharMap<'static> {
+    get_mapping_table().traditional_map()
+}
+
+pub fn get_kanji_list() -> &'static FnvHashSet<char> {
+    static CELL: OnceCell<FnvHashSet<char>> = OnceCell::new();
+    CELL.get_or_init(|| kanji_list_ordered().iter().copied().collect())
+}
+
+/// Initializes the lazily built data up front, so the first conversion doesn't pay for it, e.g.
+/// during the startup of a latency sensitive service.
+///
+/// The mapping table is generated at compile time, only the set of the kanji list (see
+/// [get_kanji_list]) is built on first use. It is idempotent and thread-safe, calling it again or
+/// concurrently does nothing.
+pub fn warm_up() {
+    get_kanji_list();
+}
+
+/// The japanese kanji list in the order of `kanji_list_topological.txt`, where a kanji comes after
+/// the kanji it is composed of. Use [is_known_kanji] for membership tests.
+pub fn kanji_list_ordered() -> &'static [char] {
+    generated::KANJI_LIST
+}
+
+/// Returns an owned copy of the entries keyed by their japanese kanji, see [get_hashmap].
+///
+/// Mutating the copy doesn't affect the embedded mapping table.
+pub fn clone_mapping() -> FnvHashMap<char, Entry> {
+    get_hashmap()
+        .iter()
+        .map(|(key, entry)| (*key, entry.clone()))
+        .collect()
+}
+
+/// Returns an owned copy of the japanese kanji list, see [get_kanji_list].
+///
+/// Mutating the copy doesn't affect the cached kanji list.
+pub fn clone_kanji_list() -> FnvHashSet<char> {
+    get_kanji_list().clone()
+}
+
+/// Returns true if the character is in the japanese kanji list.
+pub fn is_known_kanji(cha: char) -> bool {
+    get_kanji_list().contains(&cha)
+}
+
+/// Returns true if the character is a simplified chinese candidate of an entry, which is a key
+/// like in [lookup].
+pub fn is_known_simplified(cha: char) -> bool {
+    get_simplified_hashmap().contains_key(&cha)
+}
+
+/// Returns true if the character is a traditional chinese candidate of an entry, which is a key
+/// like in [lookup].
+pub fn is_known_traditional(cha: char) -> bool {
+    get_traditional_hashmap().contains_key(&cha)
+}
+
+/// Returns the mapping table entry for a character.
+///
+/// Valid keys are the japanese kanji of an entry, its first traditional and its first simplified
+/// chinese candidate, and further candidates without an entry of their own, see
+/// [MappingTable::from_entries]. A japanese kanji always resolves to its own entry. Otherwise the
+/// traditional forms are checked before the simplified forms.
+pub fn lookup(cha: char) -> Option<&'static Entry> {
+    get_mapping_table().lookup(cha)
+}
+
+/// Returns the id of the entry returned by [lookup], so chars of the same entry have the same id.
+///
+/// The id is the index of the entry in the embedded mapping table, see [entry_by_id]. It is stable
+/// as long as the [dataset_version] doesn't change.
+pub fn entry_id(cha: char) -> Option<usize> {
+    get_mapping_table().entry_id(cha)
+}
+
+/// Returns the entry with the id, see [entry_id].
+pub fn entry_by_id(id: usize) -> Option<&'static Entry> {
+    get_mapping_table().entries().get(id)
+}
+
+/// Iterates over the entries of the mapping table, sorted by their japanese kanji.
+///
+/// Every entry is returned exactly once, unlike the values of the chinese maps, where an entry
+/// is stored under each of its candidates.
+pub fn entries() -> impl Iterator<Item = &'static Entry> {
+    get_hashmap().values()
+}
+
+/// Returns the entries matching the predicate, sorted by their japanese kanji, see [entries].
+///
+/// E.g. the entries where the simplified form differs from the japanese kanji:
+/// ```
+/// use kanji_hanzi_converter::entries_where;
+///
+/// let changed = entries_where(|entry| entry.simplified_chinese.first() != Some(&entry.japanese));
+/// assert!(changed.iter().any(|entry| entry.japanese == '発'));
+/// ```
+pub fn entries_where<F: Fn(&Entry) -> bool>(pred: F) -> Vec<&'static Entry> {
+    entries().filter(|entry| pred(entry)).collect()
+}
+
+/// Statistics of the embedded mapping table, e.g. how many entries have multiple candidates.
+pub fn stats() -> MappingStats {
+    get_mapping_table().stats()
+}
+
+/// All related forms of a character across the three scripts, see [related_forms].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelatedForms {
+    pub japanese: char,
+    pub traditional: Vec<char>,
+    pub simplified: Vec<char>,
+}
+
+impl From<&Entry> for RelatedForms {
+    fn from(entry: &Entry) -> Self {
+        RelatedForms {
+            japanese: entry.japanese,
+            traditional: entry.traditional_chinese.to_vec(),
+            simplified: entry.simplified_chinese.to_vec(),
+        }
+    }
+}
+
+/// Returns all related forms of a character, regardless of the script of the character.
+///
+/// Returns `None` if the character is not in the dataset. The entry is resolved like in [lookup].
+///
+/// ```
+/// # #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+/// # {
+/// use kanji_hanzi_converter::related_forms;
+///
+/// let forms = related_forms('學').unwrap();
+/// assert_eq!(forms.japanese, '学');
+/// assert_eq!(forms.traditional, vec!['學']);
+/// assert_eq!(forms.simplified, vec!['学']);
+/// assert_eq!(related_forms('学'), Some(forms));
+/// // The dataset also has an entry for the variant "斈", but it is not in the kanji list
+/// assert_eq!(related_forms('斈'), None);
+/// # }
+/// ```
+pub fn related_forms(cha: char) -> Option<RelatedForms> {
+    lookup(cha).map(RelatedForms::from)
+}
+
+/// Returns true if the two characters are connected by the mapping table, see [relation_path].
+pub fn are_related(a: char, b: char) -> bool {
+    relation_path(a, b).is_some()
+}
 
-    let kanji_list = get_kanji_list();
-    for line in mapping.lines() {
-        if let Some(entry) = Entry::from_line(line) {
-            if !kanji_list.contains(&entry.japanese) {
+/// Returns the shortest path from `a` to `b` in the graph of the mapping table, including both
+/// characters, or `None` if they are not connected.
+///
+/// Every entry connects its japanese kanji with its traditional and simplified candidates, so
+/// chars of the same entry are related via the japanese kanji. Chars that are candidates of
+/// multiple entries connect those entries.
+///
+/// ```
+/// # #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+/// # {
+/// use kanji_hanzi_converter::relation_path;
+///
+/// assert_eq!(relation_path('学', '學'), Some(vec!['学', '學']));
+/// assert_eq!(relation_path('医', '毉'), Some(vec!['医', '毉']));
+/// assert_eq!(relation_path('醫', '毉'), Some(vec!['醫', '医', '毉']));
+/// # }
+/// ```
+pub fn relation_path(a: char, b: char) -> Option<Vec<char>> {
+    if a == b {
+        return lookup(a).map(|_| Vec::from([a]));
+    }
+    let mut neighbors: FnvHashMap<char, Vec<char>> = FnvHashMap::default();
+    for entry in entries() {
+        let candidates = entry.traditional_chinese.iter();
+        for cha in candidates.chain(entry.simplified_chinese.iter()) {
+            if *cha != entry.japanese {
+                neighbors.entry(entry.japanese).or_default().push(*cha);
+                neighbors.entry(*cha).or_default().push(entry.japanese);
+            }
+        }
+    }
+    // Breadth first search, remembering the char we came from
+    let mut previous: FnvHashMap<char, char> = FnvHashMap::default();
+    let mut queue = alloc::collections::VecDeque::from([a]);
+    while let Some(cha) = queue.pop_front() {
+        for next in neighbors.get(&cha).into_iter().flatten() {
+            if *next == a || previous.contains_key(next) {
                 continue;
             }
-            hashmap.insert(entry.japanese, entry.clone());
-
-            // Only the first entry
-            for val in entry.traditional_chinese.iter().take(1) {
-                // Dont' overwrite existing entries
-                if hashmap.contains_key(val) {
-                    continue;
+            previous.insert(*next, cha);
+            if *next == b {
+                let mut path = Vec::from([b]);
+                while let Some(prev) = previous.get(path.last().unwrap()) {
+                    path.push(*prev);
                 }
-                hashmap.insert(*val, entry.clone());
+                path.reverse();
+                return Some(path);
             }
-            for val in entry.simplified_chinese.iter().take(1) {
-                if hashmap.contains_key(val) {
-                    continue;
+            queue.push_back(*next);
+        }
+    }
+    None
+}
+
+/// Returns the version of the embedded mapping table, a hash of `kanji_mapping_table.txt`, like
+/// `fnv1a64:0123456789abcdef`. It changes whenever the table changes, so it can be recorded to
+/// track which mapping produced a conversion.
+///
+/// The hash is of the whole file, a subset selected at build time is not reflected.
+pub fn dataset_version() -> &'static str {
+    generated::DATASET_VERSION
+}
+
+/// Returns the contents of the embedded `kanji_mapping_table.txt`, including its header. It can
+/// be parsed with [load_mapping_from_str].
+pub fn raw_mapping_table() -> &'static str {
+    include_str!("../kanji_mapping_table.txt")
+}
+
+/// Serializes the whole mapping as JSON object, keyed by japanese kanji and sorted by key.
+#[cfg(feature = "serde")]
+pub fn dump_mapping_json() -> String {
+    let map: alloc::collections::BTreeMap<&char, &Entry> = get_hashmap().iter().collect();
+    serde_json::to_string(&map).expect("serializing the mapping can't fail")
+}
+
+/// Exports the whole mapping as JSON array, with one object per entry, sorted by the japanese
+/// kanji, like `[{"jp":"学","trad":["學"],"simp":["学"]}, ...]`.
+///
+/// Unlike [dump_mapping_json], the field names are short and stable, so it can be consumed
+/// directly by e.g. JavaScript clients. The output is deterministic.
+#[cfg(feature = "serde")]
+pub fn export_json() -> String {
+    #[derive(serde::Serialize)]
+    struct JsonEntry<'a> {
+        jp: char,
+        trad: &'a [char],
+        simp: &'a [char],
+    }
+    let entries: Vec<JsonEntry> = entries()
+        .map(|entry| JsonEntry {
+            jp: entry.japanese,
+            trad: &entry.traditional_chinese,
+            simp: &entry.simplified_chinese,
+        })
+        .collect();
+    serde_json::to_string(&entries).expect("serializing the mapping can't fail")
+}
+
+/// Returns the flat mapping to Simplified Chinese as `(source, simplified)` pairs, sorted by the
+/// source char, without duplicate sources. Every char of the mapping table is a source, and is
+/// paired with its conversion by [convert_char_to_simplified]. Chars that are already simplified
+/// are left out.
+#[cfg(feature = "direction-simplified")]
+pub fn simplified_pairs() -> Vec<(char, char)> {
+    pairs(TargetScript::Simplified)
+}
+
+/// Returns the flat mapping to Traditional Chinese as `(source, traditional)` pairs, see
+/// [simplified_pairs].
+#[cfg(feature = "direction-traditional")]
+pub fn traditional_pairs() -> Vec<(char, char)> {
+    pairs(TargetScript::Traditional)
+}
+
+/// Returns the flat mapping to Japanese Kanji as `(source, japanese)` pairs, see
+/// [simplified_pairs].
+#[cfg(feature = "direction-japanese")]
+pub fn japanese_pairs() -> Vec<(char, char)> {
+    pairs(TargetScript::Japanese)
+}
+
+fn pairs(target: TargetScript) -> Vec<(char, char)> {
+    let convert_char = target.char_converter();
+    let sources: alloc::collections::BTreeSet<char> = get_hashmap()
+        .keys()
+        .chain(get_traditional_hashmap().keys())
+        .chain(get_simplified_hashmap().keys())
+        .copied()
+        .collect();
+    sources
+        .into_iter()
+        .filter_map(|source| {
+            let converted = convert_char(source)?;
+            (converted != source).then_some((source, converted))
+        })
+        .collect()
+}
+
+/// Exports the whole mapping as CSV, with one row per entry, sorted by the japanese kanji.
+///
+/// The columns are `japanese,traditional,simplified`. Multiple candidates are joined by commas,
+/// and an empty candidate list is written as `N/A`, like in the mapping table.
+pub fn export_csv() -> String {
+    let mut csv = String::from("japanese,traditional,simplified\n");
+    for entry in entries() {
+        csv.push(entry.japanese);
+        for candidates in [&entry.traditional_chinese, &entry.simplified_chinese] {
+            csv.push(',');
+            push_csv_candidates(&mut csv, candidates);
+        }
+        csv.push('\n');
+    }
+    csv
+}
+
+fn push_csv_candidates(csv: &mut String, candidates: &[char]) {
+    match candidates {
+        [] => csv.push_str("N/A"),
+        [cha] => csv.push(*cha),
+        _ => {
+            // Quote the field, since it contains commas
+            csv.push('"');
+            for (i, cha) in candidates.iter().enumerate() {
+                if i != 0 {
+                    csv.push(',');
                 }
-                hashmap.insert(*val, entry.clone());
+                csv.push(*cha);
             }
+            csv.push('"');
         }
     }
-    hashmap
 }
 
-pub fn get_hashmap() -> &'static FnvHashMap<char, Entry> {
-    static CELL: OnceCell<FnvHashMap<char, Entry>> = OnceCell::new();
-    CELL.get_or_init(|| _get_hashmap())
+/// Converts a single character to Traditional Chinese.
+///
+/// Characters which are already traditional chinese are returned unchanged.
+///
+/// Returns `None` if there is no mapping for the character. This includes characters that are in
+/// the kanji list, but have `N/A` as traditional chinese entry in the mapping table.
+#[cfg(feature = "direction-traditional")]
+pub fn convert_char_to_traditional(cha: char) -> Option<char> {
+    get_mapping_table().convert_char_to_traditional(cha)
 }
 
-pub fn get_kanji_list() -> &'static FnvHashSet<char> {
-    static CELL: OnceCell<FnvHashSet<char>> = OnceCell::new();
-    CELL.get_or_init(|| {
-        let list = include_str!("../kanji_list_topological.txt");
-        list.lines()
-            .map(|line| line.trim().chars().next().unwrap())
-            .collect()
-    })
+/// Converts a single character to Simplified Chinese.
+///
+/// Characters which are already simplified chinese are returned unchanged.
+///
+/// Returns `None` if there is no mapping for the character. This includes characters that are in
+/// the kanji list, but have `N/A` as simplified chinese entry in the mapping table.
+#[cfg(feature = "direction-simplified")]
+pub fn convert_char_to_simplified(cha: char) -> Option<char> {
+    get_mapping_table().convert_char_to_simplified(cha)
+}
+
+/// Converts a single character to Japanese Kanji.
+///
+/// Returns `None` if there is no mapping for the character.
+#[cfg(feature = "direction-japanese")]
+pub fn convert_char_to_japanese(cha: char) -> Option<char> {
+    get_mapping_table().convert_char_to_japanese(cha)
+}
+
+/// Returns all traditional chinese candidates for a character, in the order of the mapping table.
+///
+/// The first candidate is the one used by [convert_char_to_traditional], unless the character
+/// itself is a traditional candidate. Returns an empty slice if there is no mapping for the
+/// character.
+#[cfg(feature = "direction-traditional")]
+pub fn all_traditional_candidates(cha: char) -> &'static [char] {
+    get_mapping_table().all_traditional_candidates(cha)
+}
+
+/// Returns all simplified chinese candidates for a character, in the order of the mapping table.
+///
+/// The first candidate is the one used by [convert_char_to_simplified], unless the character
+/// itself is a simplified candidate. Returns an empty slice if there is no mapping for the
+/// character.
+#[cfg(feature = "direction-simplified")]
+pub fn all_simplified_candidates(cha: char) -> &'static [char] {
+    get_mapping_table().all_simplified_candidates(cha)
+}
+
+/// Returns the nth traditional chinese candidate of the character, see
+/// [all_traditional_candidates]. The candidates are in the order of the mapping table.
+///
+/// Returns `None` if `n` is out of range, e.g. if there is no mapping for the character.
+#[cfg(feature = "direction-traditional")]
+pub fn convert_char_to_traditional_nth(cha: char, n: usize) -> Option<char> {
+    all_traditional_candidates(cha).get(n).copied()
+}
+
+/// Returns the nth simplified chinese candidate of the character, see
+/// [all_simplified_candidates]. The candidates are in the order of the mapping table.
+///
+/// Returns `None` if `n` is out of range, e.g. if there is no mapping for the character.
+#[cfg(feature = "direction-simplified")]
+pub fn convert_char_to_simplified_nth(cha: char, n: usize) -> Option<char> {
+    all_simplified_candidates(cha).get(n).copied()
+}
+
+/// Returns every traditional chinese form of the character, across all entries that reference
+/// it as japanese, traditional or simplified form.
+///
+/// Unlike [convert_char_to_traditional], which picks one candidate, this surfaces the ambiguity.
+/// The candidates are in the order of the mapping table, duplicates are removed.
+#[cfg(feature = "direction-traditional")]
+pub fn convert_char_all_traditional(cha: char) -> Vec<char> {
+    get_mapping_table().convert_char_all_traditional(cha)
+}
+
+/// Returns every simplified chinese form of the character, across all entries that reference it
+/// as japanese, traditional or simplified form.
+///
+/// Unlike [convert_char_to_simplified], which picks one candidate, this surfaces the ambiguity,
+/// e.g. 壹 may be simplified to 壹 or 一. The candidates are in the order of the mapping table,
+/// duplicates are removed.
+#[cfg(feature = "direction-simplified")]
+pub fn convert_char_all_simplified(cha: char) -> Vec<char> {
+    get_mapping_table().convert_char_all_simplified(cha)
+}
+
+/// Returns every japanese kanji whose entry has the character as traditional or simplified form.
+///
+/// Unlike [convert_char_to_japanese], which picks one entry, this returns all alternatives. The
+/// kanji are in the order of the mapping table, duplicates are removed.
+#[cfg(feature = "direction-japanese")]
+pub fn japanese_candidates(cha: char) -> Vec<char> {
+    get_mapping_table().japanese_candidates(cha)
+}
+
+/// Returns true if converting the character to the target script needs a choice, either because
+/// its entry has more than one candidate for the target script, or because the character is a
+/// key of multiple entries, e.g. the japanese kanji of one entry and a chinese form of another.
+///
+/// Cheaper than [convert_char_all_traditional] and friends, it only looks up the char once per
+/// key map.
+pub fn is_ambiguous(cha: char, target: TargetScript) -> bool {
+    get_mapping_table().is_ambiguous(cha, target)
 }
 
 /// Converts a string of Japanese Kanji Character to Traditional Chinese Characters
 /// Leaves chars unchanged that can't be converted.
+#[cfg(feature = "direction-traditional")]
 pub fn convert_to_traditional_chinese(input: &str) -> String {
-    let mut out = String::new();
-    for cha in input.chars() {
-        if let Some(entry) = get_hashmap()
-            .get(&cha)
-            .map(|entry| entry.traditional_chinese.get(0))
-            .flatten()
-        {
-            out.push(*entry);
-        } else {
-            out.push(cha);
-        }
-    }
-    out
+    get_mapping_table().convert_to_traditional_chinese(input)
 }
 
 /// Converts a string of Japanese Kanji Character to Simplified Chinese Characters
+///
+/// The input may also be Traditional or Simplified Chinese, or a mix of the three scripts, e.g.
+/// the japanese 医, the traditional 醫 and the simplified 医 all become 医. Every char is looked
+/// up as japanese kanji, simplified and traditional chinese form, in that order, including the
+/// further candidates like the traditional 臺 of 台, see [lookup]. A char that is already a
+/// simplified form of its entry is kept, e.g. 芸, which is both a japanese kanji and a simplified
+/// char.
 /// Leaves chars unchanged that can't be converted.
+#[cfg(feature = "direction-simplified")]
 pub fn convert_to_simplified_chinese(input: &str) -> String {
-    let mut out = String::new();
+    get_mapping_table().convert_to_simplified_chinese(input)
+}
+
+/// Converts a string of Japanese Kanji Characters to both Simplified and Traditional Chinese
+/// Characters in a single pass, and returns `(simplified, traditional)`. The results are the same
+/// as of [convert_to_simplified_chinese] and [convert_to_traditional_chinese].
+/// Leaves chars unchanged that can't be converted.
+#[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+pub fn convert_to_both_chinese(input: &str) -> (String, String) {
+    let mut simplified = String::with_capacity(input.len());
+    let mut traditional = String::with_capacity(input.len());
     for cha in input.chars() {
-        if let Some(entry) = get_hashmap()
-            .get(&cha)
-            .map(|entry| entry.simplified_chinese.get(0))
-            .flatten()
-        {
-            out.push(*entry);
-        } else {
-            out.push(cha);
-        }
+        simplified.push(convert_char_to_simplified(cha).unwrap_or(cha));
+        traditional.push(convert_char_to_traditional(cha).unwrap_or(cha));
     }
-    out
+    (simplified, traditional)
 }
 
 /// Converts a string of Chinese Characters to Japanese Kanji Characters
 /// Leaves chars unchanged that can't be converted.
+#[cfg(feature = "direction-japanese")]
 pub fn convert_to_japanese_kanji(input: &str) -> String {
-    let mut out = String::new();
-    for cha in input.chars() {
-        if let Some(entry) = get_hashmap().get(&cha).map(|entry| entry.japanese) {
-            out.push(entry);
-        } else {
-            out.push(cha);
+    get_mapping_table().convert_to_japanese_kanji(input)
+}
+
+/// Normalizes old Japanese forms (Kyūjitai) like 學 to their modern forms (Shinjitai) like 学.
+///
+/// The japanese kanji of an entry is used as the modern form of its traditional chinese forms.
+/// Unlike [convert_to_japanese_kanji], simplified chinese chars are left unchanged.
+#[cfg(feature = "direction-japanese")]
+pub fn normalize_japanese(input: &str) -> String {
+    get_mapping_table().normalize_japanese(input)
+}
+
+/// Returns the canonical form of a character, so that all forms of an entry map to the same
+/// char, e.g. for search indexing. The canonical form is the japanese kanji of the entry found
+/// by [lookup]. Chars without entry are returned unchanged.
+///
+/// ```
+/// # #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+/// # {
+/// use kanji_hanzi_converter::canonicalize;
+///
+/// assert_eq!(canonicalize('學'), '学');
+/// assert_eq!(canonicalize('学'), '学');
+/// assert_eq!(canonicalize('a'), 'a');
+/// # }
+/// ```
+pub fn canonicalize(cha: char) -> char {
+    lookup(cha).map_or(cha, |entry| entry.japanese)
+}
+
+/// Replaces every char with its canonical form, see [canonicalize].
+pub fn canonicalize_str(input: &str) -> String {
+    input.chars().map(canonicalize).collect()
+}
+
+/// The script to convert to. A variant is only available with its direction feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetScript {
+    #[cfg(feature = "direction-japanese")]
+    Japanese,
+    #[cfg(feature = "direction-traditional")]
+    Traditional,
+    #[cfg(feature = "direction-simplified")]
+    Simplified,
+}
+
+impl TargetScript {
+    /// True if the simplified chinese forms are looked up before the traditional forms, when
+    /// resolving the entry of a char.
+    pub(crate) fn prefers_simplified(self) -> bool {
+        match self {
+            #[cfg(feature = "direction-japanese")]
+            TargetScript::Japanese => false,
+            #[cfg(feature = "direction-traditional")]
+            TargetScript::Traditional => false,
+            #[cfg(feature = "direction-simplified")]
+            TargetScript::Simplified => true,
+        }
+    }
+
+    /// The per char conversion function for the target.
+    fn char_converter(self) -> fn(char) -> Option<char> {
+        match self {
+            #[cfg(feature = "direction-japanese")]
+            TargetScript::Japanese => convert_char_to_japanese,
+            #[cfg(feature = "direction-traditional")]
+            TargetScript::Traditional => convert_char_to_traditional,
+            #[cfg(feature = "direction-simplified")]
+            TargetScript::Simplified => convert_char_to_simplified,
         }
     }
-    out
 }
 
-#[derive(Debug, Clone)]
-pub struct Entry {
-    pub japanese: char,
-    pub traditional_chinese: Vec<char>,
-    pub simplified_chinese: Vec<char>,
+/// Converts a single character to the target script.
+///
+/// Returns `None` if there is no mapping for the character.
+pub fn convert_char(cha: char, target: TargetScript) -> Option<char> {
+    target.char_converter()(cha)
 }
-impl Entry {
-    pub fn from_line(line: &str) -> Option<Self> {
-        let parts: Vec<&str> = line.split('\t').collect();
 
-        if parts.len() != 3 {
-            return None; // If it doesn't match the format, we'll return None.
+/// Converts a single character to the target script, together with a confidence score.
+///
+/// The score is `1 / n`, where `n` is the number of candidates of the entry used for the
+/// conversion, so a mapping with a single candidate always has a confidence of `1.0`. An entry
+/// has exactly one japanese kanji, so conversions to Japanese always have a confidence of `1.0`.
+/// A character, which is already one of the candidates of the target script, is kept as it is,
+/// so the confidence is `1.0` as well, e.g. for 柒 to Traditional Chinese.
+///
+/// Returns `None` if there is no mapping for the character.
+pub fn convert_char_with_confidence(cha: char, target: TargetScript) -> Option<(char, f32)> {
+    let converted = convert_char(cha, target)?;
+    if converted == cha && is_candidate(cha, target) {
+        return Some((converted, 1.0));
+    }
+    Some((converted, 1.0 / candidate_count(cha, target) as f32))
+}
+
+/// True if the char is one of the candidates of the target script of its own entries.
+fn is_candidate(cha: char, target: TargetScript) -> bool {
+    match target {
+        #[cfg(feature = "direction-japanese")]
+        TargetScript::Japanese => get_hashmap().contains_key(&cha),
+        #[cfg(feature = "direction-traditional")]
+        TargetScript::Traditional => all_traditional_candidates(cha).contains(&cha),
+        #[cfg(feature = "direction-simplified")]
+        TargetScript::Simplified => all_simplified_candidates(cha).contains(&cha),
+    }
+}
+
+/// Number of candidates of the entry used to convert the char to the target script.
+fn candidate_count(cha: char, target: TargetScript) -> usize {
+    match target {
+        #[cfg(feature = "direction-japanese")]
+        TargetScript::Japanese => usize::from(convert_char_to_japanese(cha).is_some()),
+        #[cfg(feature = "direction-traditional")]
+        TargetScript::Traditional => all_traditional_candidates(cha).len(),
+        #[cfg(feature = "direction-simplified")]
+        TargetScript::Simplified => all_simplified_candidates(cha).len(),
+    }
+}
+
+/// The result of [convert_detailed].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conversion {
+    /// The converted string.
+    pub output: String,
+    /// True if any char was ambiguous.
+    pub lossy: bool,
+    /// Byte offsets in the input of the chars, whose conversion has a confidence below `1.0`, see
+    /// [convert_char_with_confidence].
+    pub ambiguous_positions: Vec<usize>,
+}
+
+/// Converts a string to the target script like [convert], and also reports which chars were
+/// ambiguous, i.e. converted with a confidence below `1.0` by [convert_char_with_confidence].
+/// Conversions to Japanese are never ambiguous, since an entry has exactly one japanese kanji.
+pub fn convert_detailed(input: &str, target: TargetScript) -> Conversion {
+    let mut output = String::with_capacity(input.len());
+    let mut ambiguous_positions = Vec::new();
+    for (pos, cha) in input.char_indices() {
+        let Some((converted, confidence)) = convert_char_with_confidence(cha, target) else {
+            output.push(cha);
+            continue;
+        };
+        output.push(converted);
+        if confidence < 1.0 {
+            ambiguous_positions.push(pos);
         }
+    }
+    Conversion {
+        output,
+        lossy: !ambiguous_positions.is_empty(),
+        ambiguous_positions,
+    }
+}
 
-        let jap = parts[0].chars().next().unwrap();
-        let traditional_chinese: Vec<char> = parts[1]
-            .split(',')
-            .filter_map(|s| {
-                let trimmed = s.trim();
-                if trimmed == "N/A" {
-                    None
-                } else {
-                    trimmed.chars().next()
-                }
-            })
-            .collect();
+/// Counts which chars are converted, accumulated over many inputs, e.g. to profile a corpus.
+///
+/// To process documents in parallel, use one accumulator per thread and [merge](Self::merge)
+/// them afterwards.
+///
+/// ```
+/// # #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+/// # {
+/// use kanji_hanzi_converter::{ConversionStats, TargetScript};
+///
+/// let mut stats = ConversionStats::new();
+/// stats.observe("醫學と醫者", TargetScript::Simplified);
+/// assert_eq!(stats.report()[0], ('醫', '医', 2));
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConversionStats {
+    counts: FnvHashMap<(char, char), usize>,
+}
 
-        let simplified_chinese: Vec<char> = parts[2]
-            .split(',')
-            .filter_map(|s| {
-                let trimmed = s.trim();
-                if trimmed == "N/A" {
-                    None
-                } else {
-                    trimmed.chars().next()
-                }
-            })
+impl ConversionStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Converts the input to the target script like [convert], and counts every char that is
+    /// changed by the conversion.
+    pub fn observe(&mut self, input: &str, target: TargetScript) {
+        let convert_char = target.char_converter();
+        for cha in input.chars() {
+            if let Some(converted) = convert_char(cha).filter(|converted| *converted != cha) {
+                *self.counts.entry((cha, converted)).or_default() += 1;
+            }
+        }
+    }
+
+    /// Adds the counts of another accumulator.
+    pub fn merge(&mut self, other: &ConversionStats) {
+        for (conversion, count) in &other.counts {
+            *self.counts.entry(*conversion).or_default() += count;
+        }
+    }
+
+    /// Returns the conversions `(from, to, count)`, most frequent first. Conversions with the
+    /// same count are sorted by char.
+    pub fn report(&self) -> Vec<(char, char, usize)> {
+        let mut report: Vec<(char, char, usize)> = self
+            .counts
+            .iter()
+            .map(|((from, to), count)| (*from, *to, *count))
             .collect();
+        report.sort_unstable_by_key(|(from, to, count)| (core::cmp::Reverse(*count), *from, *to));
+        report
+    }
+}
 
-        Some(Entry {
-            japanese: jap,
-            traditional_chinese,
-            simplified_chinese,
-        })
+/// Converts a string to the target script.
+/// Leaves chars unchanged that can't be converted.
+pub fn convert(input: &str, target: TargetScript) -> String {
+    let convert_char = target.char_converter();
+    input
+        .chars()
+        .map(|cha| convert_char(cha).unwrap_or(cha))
+        .collect()
+}
+
+/// Converts a string to the target script like [convert], and also maps full-width ASCII
+/// (U+FF01–U+FF5E) to half-width and the ideographic space to a regular space.
+pub fn convert_normalize_width(input: &str, target: TargetScript) -> String {
+    let convert_char = target.char_converter();
+    input
+        .chars()
+        .map(|cha| convert_char(cha).unwrap_or_else(|| to_half_width(cha)))
+        .collect()
+}
+
+/// Maps full-width ASCII (U+FF01–U+FF5E) to half-width and the ideographic space to a regular
+/// space. Other chars are returned unchanged.
+pub fn to_half_width(cha: char) -> char {
+    match cha {
+        '\u{3000}' => ' ',
+        '\u{FF01}'..='\u{FF5E}' => char::from_u32(cha as u32 - 0xFF01 + 0x21).unwrap_or(cha),
+        _ => cha,
     }
 }
-#[cfg(test)]
-mod tests {
-    use super::*;
 
-    #[test]
-    fn test_entry_from_line() {
-        let line = "七\t七,柒,漆\t七,柒,漆";
-        let entry = Entry::from_line(line).unwrap();
+/// Full-width forms of the half-width katakana and punctuation U+FF61–U+FF9F.
+const FULL_WIDTH_KATAKANA: [char; 63] = [
+    '。', '「', '」', '、', '・', 'ヲ', 'ァ', 'ィ', 'ゥ', 'ェ', 'ォ', 'ャ', 'ュ', 'ョ', 'ッ', 'ー',
+    'ア', 'イ', 'ウ', 'エ', 'オ', 'カ', 'キ', 'ク', 'ケ', 'コ', 'サ', 'シ', 'ス', 'セ', 'ソ', 'タ',
+    'チ', 'ツ', 'テ', 'ト', 'ナ', 'ニ', 'ヌ', 'ネ', 'ノ', 'ハ', 'ヒ', 'フ', 'ヘ', 'ホ', 'マ', 'ミ',
+    'ム', 'メ', 'モ', 'ヤ', 'ユ', 'ヨ', 'ラ', 'リ', 'ル', 'レ', 'ロ', 'ワ', 'ン', '゛', '゜',
+];
 
-        assert_eq!(entry.japanese, '七');
-        assert_eq!(entry.traditional_chinese, vec!['七', '柒', '漆']);
-        assert_eq!(entry.simplified_chinese, vec!['七', '柒', '漆']);
+/// Maps half-width katakana and punctuation (U+FF61–U+FF9F) to full-width. Other chars are
+/// returned unchanged.
+///
+/// The sound marks ﾞ and ﾟ become the standalone marks ゛ and ゜, see [widen_katakana] to combine
+/// them with the preceding katakana.
+pub fn to_full_width_katakana(cha: char) -> char {
+    match cha {
+        '\u{FF61}'..='\u{FF9F}' => FULL_WIDTH_KATAKANA[(cha as u32 - 0xFF61) as usize],
+        _ => cha,
+    }
+}
 
-        let line_with_na = "鰄\tN/A\tN/A";
-        assert!(Entry::from_line(line_with_na).is_some()); // Still return an Entry even if TC and SC are "N/A"
+/// Maps half-width katakana to full-width like [to_full_width_katakana], and combines a katakana
+/// followed by a sound mark into a single char, e.g. ｶﾞ becomes ガ and ﾊﾟ becomes パ.
+pub fn widen_katakana(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    while let Some(cha) = chars.next() {
+        let widened = to_full_width_katakana(cha);
+        match chars.peek().and_then(|mark| with_sound_mark(cha, *mark)) {
+            Some(combined) => {
+                out.push(combined);
+                chars.next();
+            }
+            None => out.push(widened),
+        }
+    }
+    out
+}
 
-        let incorrect_format_line = "just some random text";
-        assert!(Entry::from_line(incorrect_format_line).is_none()); // Should not be able to parse this line
+/// The full-width voiced (ﾞ) or semi-voiced (ﾟ) form of a half-width katakana, if it exists.
+pub(crate) fn with_sound_mark(half_width: char, mark: char) -> Option<char> {
+    let base = to_full_width_katakana(half_width);
+    if base == half_width {
+        return None;
+    }
+    // The voiced and semi-voiced forms directly follow the unvoiced katakana
+    match mark {
+        '\u{FF9E}' if base == 'ウ' => Some('ヴ'),
+        '\u{FF9E}' if "カキクケコサシスセソタチツテトハヒフヘホ".contains(base) => {
+            char::from_u32(base as u32 + 1)
+        }
+        '\u{FF9F}' if "ハヒフヘホ".contains(base) => char::from_u32(base as u32 + 2),
+        _ => None,
+    }
+}
+
+/// Converts a string to the target script, using the surrounding chars to pick between the
+/// candidates of ambiguous chars.
+///
+/// `bigrams` are known compounds of two chars in the target script. For a char with multiple
+/// candidates, the first candidate that forms a bigram with one of the converted chars at most
+/// `window` chars before or after it is used. Without a match the char is converted like in
+/// [convert].
+///
+/// ```
+/// # #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+/// # {
+/// use kanji_hanzi_converter::{convert_with_context, TargetScript};
+///
+/// let bigrams = [('颱', '風')];
+/// assert_eq!(convert_with_context("台风", TargetScript::Traditional, 1, &bigrams), "颱風");
+/// assert_eq!(convert_with_context("台风", TargetScript::Traditional, 1, &[]), "台風");
+/// # }
+/// ```
+pub fn convert_with_context(
+    input: &str,
+    target: TargetScript,
+    window: usize,
+    bigrams: &[(char, char)],
+) -> String {
+    let convert_char = target.char_converter();
+    let chars: Vec<char> = input.chars().collect();
+    let converted: Vec<char> = chars
+        .iter()
+        .map(|cha| convert_char(*cha).unwrap_or(*cha))
+        .collect();
+    let candidates: fn(char) -> &'static [char] = match target {
+        #[cfg(feature = "direction-japanese")]
+        TargetScript::Japanese => |_| &[],
+        #[cfg(feature = "direction-traditional")]
+        TargetScript::Traditional => all_traditional_candidates,
+        #[cfg(feature = "direction-simplified")]
+        TargetScript::Simplified => all_simplified_candidates,
+    };
+    let mut out = String::with_capacity(input.len());
+    for (pos, cha) in chars.iter().enumerate() {
+        let candidates = candidates(*cha);
+        let before = &converted[pos.saturating_sub(window)..pos];
+        let after = &converted[pos + 1..(pos + 1 + window).min(converted.len())];
+        let in_context = candidates.iter().find(|candidate| {
+            before
+                .iter()
+                .any(|prev| bigrams.contains(&(*prev, **candidate)))
+                || after
+                    .iter()
+                    .any(|next| bigrams.contains(&(**candidate, *next)))
+        });
+        match in_context {
+            Some(candidate) if candidates.len() > 1 => out.push(*candidate),
+            _ => out.push(converted[pos]),
+        }
+    }
+    out
+}
+
+/// Converts a string of Simplified Chinese Characters to Traditional Chinese Characters
+/// Leaves chars unchanged that can't be converted.
+///
+/// Unlike going through [convert_to_japanese_kanji], the lookup is done only on the simplified
+/// chinese forms, so e.g. "学" resolves to "學", even though "学" is also a japanese kanji.
+#[cfg(feature = "direction-traditional")]
+pub fn convert_simplified_to_traditional(input: &str) -> String {
+    get_mapping_table().convert_simplified_to_traditional(input)
+}
+
+/// Converts a string of Traditional Chinese Characters to Simplified Chinese Characters
+/// Leaves chars unchanged that can't be converted.
+///
+/// The lookup is done only on the traditional chinese forms, so a traditional char resolves to
+/// the entry where it is a traditional candidate, even if it is also a japanese kanji. Multiple
+/// traditional chars can merge into the same simplified char, e.g. 發 and 髮 both become 发.
+#[cfg(feature = "direction-simplified")]
+pub fn convert_traditional_to_simplified(input: &str) -> String {
+    get_mapping_table().convert_traditional_to_simplified(input)
+}
+
+/// Conversion methods on `str`, delegating to the free functions of this crate.
+///
+/// ```
+/// # #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+/// # {
+/// use kanji_hanzi_converter::KanjiHanziExt;
+///
+/// assert_eq!("醫生".to_simplified_chinese(), "医生");
+/// # }
+/// ```
+pub trait KanjiHanziExt {
+    /// See [convert_to_simplified_chinese].
+    #[cfg(feature = "direction-simplified")]
+    fn to_simplified_chinese(&self) -> String;
+    /// See [convert_to_traditional_chinese].
+    #[cfg(feature = "direction-traditional")]
+    fn to_traditional_chinese(&self) -> String;
+    /// See [convert_to_japanese_kanji].
+    #[cfg(feature = "direction-japanese")]
+    fn to_japanese_kanji(&self) -> String;
+}
+
+impl KanjiHanziExt for str {
+    #[cfg(feature = "direction-simplified")]
+    fn to_simplified_chinese(&self) -> String {
+        convert_to_simplified_chinese(self)
+    }
+
+    #[cfg(feature = "direction-traditional")]
+    fn to_traditional_chinese(&self) -> String {
+        convert_to_traditional_chinese(self)
+    }
+
+    #[cfg(feature = "direction-japanese")]
+    fn to_japanese_kanji(&self) -> String {
+        convert_to_japanese_kanji(self)
+    }
+}
+
+/// The guessed script of a text, see [detect_script].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptGuess {
+    Japanese,
+    SimplifiedChinese,
+    TraditionalChinese,
+    /// Multiple scripts are equally likely.
+    Mixed,
+    /// The text contains no characters that hint at a script.
+    Unknown,
+}
+
+/// Guesses the script of a text.
+///
+/// Every character is counted for at most one script:
+/// - Simplified Chinese, if it is a simplified chinese form whose traditional form differs, e.g. "医"
+/// - Traditional Chinese, if it is a traditional chinese form whose simplified form differs, e.g.
+///   "醫"
+/// - Japanese, if it is a kana, or a kanji that differs from both chinese forms, e.g. "沢"
+///
+/// The script with the most characters wins, a tie results in [ScriptGuess::Mixed]. Characters
+/// which are the same in all scripts don't count, so e.g. "生" results in [ScriptGuess::Unknown].
+pub fn detect_script(input: &str) -> ScriptGuess {
+    let mut japanese = 0;
+    let mut simplified = 0;
+    let mut traditional = 0;
+    for cha in input.chars() {
+        if is_kana(cha) {
+            japanese += 1;
+            continue;
+        }
+        if let Some(entry) = get_simplified_hashmap().get(&cha) {
+            if !entry.traditional_chinese.contains(&cha) {
+                simplified += 1;
+                continue;
+            }
+        }
+        if let Some(entry) = get_traditional_hashmap().get(&cha) {
+            if !entry.simplified_chinese.contains(&cha) {
+                traditional += 1;
+                continue;
+            }
+        }
+        if let Some(entry) = get_hashmap().get(&cha) {
+            if entry.japanese == cha
+                && entry.traditional_chinese.first() != Some(&cha)
+                && entry.simplified_chinese.first() != Some(&cha)
+            {
+                japanese += 1;
+            }
+        }
+    }
+
+    let max = japanese.max(simplified).max(traditional);
+    if max == 0 {
+        return ScriptGuess::Unknown;
+    }
+    let candidates = [
+        (japanese, ScriptGuess::Japanese),
+        (simplified, ScriptGuess::SimplifiedChinese),
+        (traditional, ScriptGuess::TraditionalChinese),
+    ];
+    let mut winners = candidates.iter().filter(|(count, _)| *count == max);
+    match (winners.next(), winners.next()) {
+        (Some((_, script)), None) => *script,
+        _ => ScriptGuess::Mixed,
+    }
+}
+
+/// Returns the unified ideograph of a CJK Compatibility Ideograph (U+F900–U+FAFF and
+/// U+2F800–U+2FA1F), which is its canonical decomposition. Other chars are returned unchanged.
+///
+/// The mapping table only contains unified ideographs, so compatibility ideographs need to be
+/// normalized to be converted, see [ConverterBuilder::normalize_compatibility].
+#[cfg(feature = "compat-ideographs")]
+pub fn normalize_compatibility_ideograph(cha: char) -> char {
+    let pairs = generated::COMPATIBILITY_IDEOGRAPHS;
+    pairs
+        .binary_search_by_key(&cha, |(compatibility, _)| *compatibility)
+        .map_or(cha, |pos| pairs[pos].1)
+}
+
+/// Returns true if the character is in one of the CJK Unified Ideographs blocks, including the
+/// extensions. CJK punctuation, kana and the compatibility ideographs are not included.
+pub fn is_cjk_ideograph(cha: char) -> bool {
+    matches!(cha,
+        '\u{4E00}'..='\u{9FFF}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{20000}'..='\u{2A6DF}'
+        | '\u{2A700}'..='\u{2EBEF}'
+        | '\u{30000}'..='\u{323AF}')
+}
+
+/// Hiragana and Katakana, including the half-width forms.
+fn is_kana(cha: char) -> bool {
+    matches!(cha, '\u{3041}'..='\u{309F}' | '\u{30A0}'..='\u{30FF}' | '\u{FF66}'..='\u{FF9F}')
+}
+
+/// Like [convert_to_traditional_chinese], but returns the input unchanged without allocating, if
+/// no character is converted.
+#[cfg(feature = "direction-traditional")]
+pub fn convert_to_traditional_chinese_cow(input: &str) -> Cow<'_, str> {
+    get_mapping_table().convert_to_traditional_chinese_cow(input)
+}
+
+/// Like [convert_to_simplified_chinese], but returns the input unchanged without allocating, if
+/// no character is converted.
+#[cfg(feature = "direction-simplified")]
+pub fn convert_to_simplified_chinese_cow(input: &str) -> Cow<'_, str> {
+    get_mapping_table().convert_to_simplified_chinese_cow(input)
+}
+
+/// Like [convert_to_japanese_kanji], but returns the input unchanged without allocating, if no
+/// character is converted.
+#[cfg(feature = "direction-japanese")]
+pub fn convert_to_japanese_kanji_cow(input: &str) -> Cow<'_, str> {
+    get_mapping_table().convert_to_japanese_kanji_cow(input)
+}
+
+/// Returns true if converting the input to the target script changes at least one character.
+///
+/// This is a single pass without allocation, which stops at the first char that would change, so
+/// a conversion of text that is already in the target script can be skipped, see also
+/// [convert_to_simplified_chinese_cow].
+pub fn needs_conversion(input: &str, target: TargetScript) -> bool {
+    get_mapping_table().needs_conversion(input, target)
+}
+
+/// Converts to Traditional Chinese, split into segments. Runs of unchanged chars are borrowed
+/// from the input, runs of converted chars are owned, so concatenating the segments gives the
+/// result of [convert_to_traditional_chinese]. This avoids copying mostly unchanged input, e.g.
+/// for a rope.
+#[cfg(feature = "direction-traditional")]
+pub fn convert_segments_to_traditional(input: &str) -> Vec<Cow<'_, str>> {
+    convert_segments(input, convert_char_to_traditional)
+}
+
+/// Converts to Simplified Chinese, split into segments. Runs of unchanged chars are borrowed
+/// from the input, runs of converted chars are owned, so concatenating the segments gives the
+/// result of [convert_to_simplified_chinese]. This avoids copying mostly unchanged input, e.g.
+/// for a rope.
+#[cfg(feature = "direction-simplified")]
+pub fn convert_segments_to_simplified(input: &str) -> Vec<Cow<'_, str>> {
+    convert_segments(input, convert_char_to_simplified)
+}
+
+/// Converts to Japanese Kanji, split into segments. Runs of unchanged chars are borrowed from
+/// the input, runs of converted chars are owned, so concatenating the segments gives the result
+/// of [convert_to_japanese_kanji]. This avoids copying mostly unchanged input, e.g. for a rope.
+#[cfg(feature = "direction-japanese")]
+pub fn convert_segments_to_japanese(input: &str) -> Vec<Cow<'_, str>> {
+    convert_segments(input, convert_char_to_japanese)
+}
+
+fn convert_segments(input: &str, convert_char: fn(char) -> Option<char>) -> Vec<Cow<'_, str>> {
+    let mut segments = Vec::new();
+    // Start of the current run of unchanged chars
+    let mut unchanged_start = 0;
+    let mut changed = String::new();
+    for (pos, cha) in input.char_indices() {
+        match convert_char(cha).filter(|converted| *converted != cha) {
+            Some(converted) => {
+                if unchanged_start < pos {
+                    segments.push(Cow::Borrowed(&input[unchanged_start..pos]));
+                }
+                changed.push(converted);
+                unchanged_start = pos + cha.len_utf8();
+            }
+            None => {
+                if !changed.is_empty() {
+                    segments.push(Cow::Owned(core::mem::take(&mut changed)));
+                }
+            }
+        }
+    }
+    if !changed.is_empty() {
+        segments.push(Cow::Owned(changed));
+    } else if unchanged_start < input.len() {
+        segments.push(Cow::Borrowed(&input[unchanged_start..]));
+    }
+    segments
+}
+
+/// Converts many strings to Traditional Chinese, see [convert_to_traditional_chinese].
+#[cfg(feature = "direction-traditional")]
+pub fn convert_many_to_traditional(inputs: &[&str]) -> Vec<String> {
+    convert_many(inputs, convert_char_to_traditional)
+}
+
+/// Converts many strings to Simplified Chinese, see [convert_to_simplified_chinese].
+#[cfg(feature = "direction-simplified")]
+pub fn convert_many_to_simplified(inputs: &[&str]) -> Vec<String> {
+    convert_many(inputs, convert_char_to_simplified)
+}
+
+/// Converts many strings to Japanese Kanji, see [convert_to_japanese_kanji].
+#[cfg(feature = "direction-japanese")]
+pub fn convert_many_to_japanese(inputs: &[&str]) -> Vec<String> {
+    convert_many(inputs, convert_char_to_japanese)
+}
+
+/// Converts into a scratch buffer, which is reused for all inputs, so every output is allocated
+/// once with the exact size.
+fn convert_many(inputs: &[&str], convert_char: fn(char) -> Option<char>) -> Vec<String> {
+    let mut scratch = String::new();
+    inputs
+        .iter()
+        .map(|input| {
+            convert_into(input, &mut scratch, convert_char);
+            String::from(scratch.as_str())
+        })
+        .collect()
+}
+
+/// Converts to Traditional Chinese into `out`, which is cleared first. Reusing the same `out`
+/// avoids allocating for every conversion.
+#[cfg(feature = "direction-traditional")]
+pub fn convert_to_traditional_into(input: &str, out: &mut String) {
+    convert_into(input, out, convert_char_to_traditional);
+}
+
+/// Converts to Simplified Chinese into `out`, which is cleared first. Reusing the same `out`
+/// avoids allocating for every conversion.
+#[cfg(feature = "direction-simplified")]
+pub fn convert_to_simplified_into(input: &str, out: &mut String) {
+    convert_into(input, out, convert_char_to_simplified);
+}
+
+/// Converts to Japanese Kanji into `out`, which is cleared first. Reusing the same `out` avoids
+/// allocating for every conversion.
+#[cfg(feature = "direction-japanese")]
+pub fn convert_to_japanese_into(input: &str, out: &mut String) {
+    convert_into(input, out, convert_char_to_japanese);
+}
+
+fn convert_into(input: &str, out: &mut String, convert_char: fn(char) -> Option<char>) {
+    out.clear();
+    // Converted chars usually have the same length in UTF-8
+    out.reserve(input.len());
+    out.extend(input.chars().map(|cha| convert_char(cha).unwrap_or(cha)));
+}
+
+/// Converts many strings to Traditional Chinese in parallel, see [convert_many_to_traditional].
+#[cfg(all(feature = "rayon", feature = "direction-traditional"))]
+pub fn par_convert_many_to_traditional(inputs: &[&str]) -> Vec<String> {
+    par_convert_many(inputs, convert_to_traditional_chinese)
+}
+
+/// Converts many strings to Simplified Chinese in parallel, see [convert_many_to_simplified].
+#[cfg(all(feature = "rayon", feature = "direction-simplified"))]
+pub fn par_convert_many_to_simplified(inputs: &[&str]) -> Vec<String> {
+    par_convert_many(inputs, convert_to_simplified_chinese)
+}
+
+/// Converts many strings to Japanese Kanji in parallel, see [convert_many_to_japanese].
+#[cfg(all(feature = "rayon", feature = "direction-japanese"))]
+pub fn par_convert_many_to_japanese(inputs: &[&str]) -> Vec<String> {
+    par_convert_many(inputs, convert_to_japanese_kanji)
+}
+
+#[cfg(feature = "rayon")]
+fn par_convert_many(inputs: &[&str], convert: fn(&str) -> String) -> Vec<String> {
+    use rayon::prelude::*;
+
+    inputs.par_iter().map(|input| convert(input)).collect()
+}
+
+/// Like [convert_to_traditional_chinese], but calls `fallback` for chars without mapping. It
+/// returns the replacement, or `None` to drop the char.
+#[cfg(feature = "direction-traditional")]
+pub fn convert_to_traditional_with<F: FnMut(char) -> Option<char>>(
+    input: &str,
+    fallback: F,
+) -> String {
+    convert_with(input, convert_char_to_traditional, fallback)
+}
+
+/// Like [convert_to_simplified_chinese], but calls `fallback` for chars without mapping. It
+/// returns the replacement, or `None` to drop the char.
+#[cfg(feature = "direction-simplified")]
+pub fn convert_to_simplified_with<F: FnMut(char) -> Option<char>>(
+    input: &str,
+    fallback: F,
+) -> String {
+    convert_with(input, convert_char_to_simplified, fallback)
+}
+
+/// Like [convert_to_japanese_kanji], but calls `fallback` for chars without mapping. It returns
+/// the replacement, or `None` to drop the char.
+#[cfg(feature = "direction-japanese")]
+pub fn convert_to_japanese_with<F: FnMut(char) -> Option<char>>(
+    input: &str,
+    fallback: F,
+) -> String {
+    convert_with(input, convert_char_to_japanese, fallback)
+}
+
+fn convert_with(
+    input: &str,
+    convert_char: impl Fn(char) -> Option<char>,
+    mut fallback: impl FnMut(char) -> Option<char>,
+) -> String {
+    input
+        .chars()
+        .filter_map(|cha| convert_char(cha).or_else(|| fallback(cha)))
+        .collect()
+}
+
+/// Like [convert_to_traditional_chinese], but also returns the chars without mapping, together
+/// with their byte offset in the input.
+#[cfg(feature = "direction-traditional")]
+pub fn convert_to_traditional_chinese_report(input: &str) -> (String, Vec<(usize, char)>) {
+    convert_report(input, convert_char_to_traditional)
+}
+
+/// Like [convert_to_simplified_chinese], but also returns the chars without mapping, together
+/// with their byte offset in the input.
+#[cfg(feature = "direction-simplified")]
+pub fn convert_to_simplified_chinese_report(input: &str) -> (String, Vec<(usize, char)>) {
+    convert_report(input, convert_char_to_simplified)
+}
+
+/// Like [convert_to_japanese_kanji], but also returns the chars without mapping, together with
+/// their byte offset in the input.
+#[cfg(feature = "direction-japanese")]
+pub fn convert_to_japanese_kanji_report(input: &str) -> (String, Vec<(usize, char)>) {
+    convert_report(input, convert_char_to_japanese)
+}
+
+fn convert_report(
+    input: &str,
+    convert_char: impl Fn(char) -> Option<char>,
+) -> (String, Vec<(usize, char)>) {
+    let mut out = String::with_capacity(input.len());
+    let mut unmapped = Vec::new();
+    for (pos, cha) in input.char_indices() {
+        if let Some(converted) = convert_char(cha) {
+            out.push(converted);
+        } else {
+            out.push(cha);
+            unmapped.push((pos, cha));
+        }
+    }
+    (out, unmapped)
+}
+
+/// Like [convert_to_traditional_chinese], but also returns the number of chars that changed.
+///
+/// Chars without mapping and chars that are already traditional chinese don't count as changes.
+#[cfg(feature = "direction-traditional")]
+pub fn convert_to_traditional_counted(input: &str) -> (String, usize) {
+    convert_counted(input, convert_char_to_traditional)
+}
+
+/// Like [convert_to_simplified_chinese], but also returns the number of chars that changed.
+#[cfg(feature = "direction-simplified")]
+pub fn convert_to_simplified_counted(input: &str) -> (String, usize) {
+    convert_counted(input, convert_char_to_simplified)
+}
+
+/// Like [convert_to_japanese_kanji], but also returns the number of chars that changed.
+#[cfg(feature = "direction-japanese")]
+pub fn convert_to_japanese_counted(input: &str) -> (String, usize) {
+    convert_counted(input, convert_char_to_japanese)
+}
+
+fn convert_counted(input: &str, convert_char: impl Fn(char) -> Option<char>) -> (String, usize) {
+    let mut out = String::with_capacity(input.len());
+    let mut changed = 0;
+    for cha in input.chars() {
+        let converted = convert_char(cha).unwrap_or(cha);
+        if converted != cha {
+            changed += 1;
+        }
+        out.push(converted);
+    }
+    (out, changed)
+}
+
+/// Converts to Traditional Chinese and returns `(original, converted)` for every char of the input,
+/// in order. The length is always the number of chars of the input, unchanged chars map to
+/// themselves.
+#[cfg(feature = "direction-traditional")]
+pub fn convert_to_traditional_mapped(input: &str) -> Vec<(char, char)> {
+    convert_mapped(input, convert_char_to_traditional)
+}
+
+/// Converts to Simplified Chinese and returns `(original, converted)` for every char of the input,
+/// in order. The length is always the number of chars of the input, unchanged chars map to
+/// themselves.
+#[cfg(feature = "direction-simplified")]
+pub fn convert_to_simplified_mapped(input: &str) -> Vec<(char, char)> {
+    convert_mapped(input, convert_char_to_simplified)
+}
+
+/// Converts to Japanese Kanji and returns `(original, converted)` for every char of the input, in
+/// order. The length is always the number of chars of the input, unchanged chars map to
+/// themselves.
+#[cfg(feature = "direction-japanese")]
+pub fn convert_to_japanese_mapped(input: &str) -> Vec<(char, char)> {
+    convert_mapped(input, convert_char_to_japanese)
+}
+
+fn convert_mapped(input: &str, convert_char: fn(char) -> Option<char>) -> Vec<(char, char)> {
+    input
+        .chars()
+        .map(|cha| (cha, convert_char(cha).unwrap_or(cha)))
+        .collect()
+}
+
+/// Returns true if every CJK char of the input has a mapping to the target script, see
+/// [is_cjk_ideograph]. Other chars are ignored, so an input without CJK chars returns true.
+pub fn is_all_convertible(input: &str, target: TargetScript) -> bool {
+    input
+        .chars()
+        .filter(|cha| is_cjk_ideograph(*cha))
+        .all(|cha| convert_char(cha, target).is_some())
+}
+
+/// Converts to Simplified Chinese like [convert_to_simplified_chinese], and also returns the
+/// kokuji of the input, in the order of their first occurrence.
+///
+/// A kokuji is a kanji of the japanese kanji list without chinese equivalent, i.e. with `N/A` as
+/// simplified form in the mapping table, like 峠. Unlike [unconvertible_chars], chars outside of
+/// the kanji list are not reported, since they are gaps in the data rather than kokuji.
+#[cfg(feature = "direction-simplified")]
+pub fn convert_to_simplified_reporting_kokuji(input: &str) -> (String, Vec<char>) {
+    let mut kokuji = Vec::new();
+    let mut out = String::with_capacity(input.len());
+    for cha in input.chars() {
+        match convert_char_to_simplified(cha) {
+            Some(converted) => out.push(converted),
+            None => {
+                if is_known_kanji(cha) && !kokuji.contains(&cha) {
+                    kokuji.push(cha);
+                }
+                out.push(cha);
+            }
+        }
+    }
+    (out, kokuji)
+}
+
+/// Returns the proportion of the chars, that are in the kanji list and have a mapping to the
+/// target script, e.g. to check the coverage of a kanji frequency list.
+///
+/// Chars that are not in the kanji list count as uncovered. Returns `0.0` for an empty slice.
+pub fn coverage(chars: &[char], target: TargetScript) -> f32 {
+    if chars.is_empty() {
+        return 0.0;
+    }
+    let covered = chars
+        .iter()
+        .filter(|cha| is_known_kanji(**cha) && convert_char(**cha, target).is_some())
+        .count();
+    covered as f32 / chars.len() as f32
+}
+
+/// A span of a conversion diff, see [convert_diff_to_simplified].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffSpan {
+    /// A run of chars that are unchanged by the conversion.
+    Unchanged(String),
+    /// A converted char.
+    Changed { from: char, to: char },
+}
+
+/// Converts to Traditional Chinese and returns the result as diff to the input. Consecutive
+/// unchanged chars are coalesced into one span.
+#[cfg(feature = "direction-traditional")]
+pub fn convert_diff_to_traditional(input: &str) -> Vec<DiffSpan> {
+    convert_diff(input, convert_char_to_traditional)
+}
+
+/// Converts to Simplified Chinese and returns the result as diff to the input. Consecutive
+/// unchanged chars are coalesced into one span.
+#[cfg(feature = "direction-simplified")]
+pub fn convert_diff_to_simplified(input: &str) -> Vec<DiffSpan> {
+    convert_diff(input, convert_char_to_simplified)
+}
+
+/// Converts to Japanese Kanji and returns the result as diff to the input. Consecutive unchanged
+/// chars are coalesced into one span.
+#[cfg(feature = "direction-japanese")]
+pub fn convert_diff_to_japanese(input: &str) -> Vec<DiffSpan> {
+    convert_diff(input, convert_char_to_japanese)
+}
+
+fn convert_diff(input: &str, convert_char: fn(char) -> Option<char>) -> Vec<DiffSpan> {
+    let mut spans = Vec::new();
+    for cha in input.chars() {
+        let converted = convert_char(cha).unwrap_or(cha);
+        if converted != cha {
+            spans.push(DiffSpan::Changed {
+                from: cha,
+                to: converted,
+            });
+        } else if let Some(DiffSpan::Unchanged(run)) = spans.last_mut() {
+            run.push(cha);
+        } else {
+            spans.push(DiffSpan::Unchanged(String::from(cha)));
+        }
+    }
+    spans
+}
+
+/// Error of the `try_convert_*` functions, e.g. [try_convert_to_simplified].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConversionError {
+    /// Byte offset of the char in the input.
+    pub offset: usize,
+    /// The first CJK char without mapping.
+    pub cha: char,
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "no mapping for {:?} at byte offset {}",
+            self.cha, self.offset
+        )
+    }
+}
+
+impl core::error::Error for ConversionError {}
+
+/// Like [convert_to_traditional_chinese], but fails on the first CJK char without mapping, see
+/// [is_cjk_ideograph]. Other chars without mapping, like punctuation, are passed through.
+#[cfg(feature = "direction-traditional")]
+pub fn try_convert_to_traditional(input: &str) -> Result<String, ConversionError> {
+    try_convert(input, convert_char_to_traditional)
+}
+
+/// Like [convert_to_simplified_chinese], but fails on the first CJK char without mapping, see
+/// [is_cjk_ideograph]. Other chars without mapping, like punctuation, are passed through.
+#[cfg(feature = "direction-simplified")]
+pub fn try_convert_to_simplified(input: &str) -> Result<String, ConversionError> {
+    try_convert(input, convert_char_to_simplified)
+}
+
+/// Like [convert_to_japanese_kanji], but fails on the first CJK char without mapping, see
+/// [is_cjk_ideograph]. Other chars without mapping, like punctuation, are passed through.
+#[cfg(feature = "direction-japanese")]
+pub fn try_convert_to_japanese(input: &str) -> Result<String, ConversionError> {
+    try_convert(input, convert_char_to_japanese)
+}
+
+fn try_convert(
+    input: &str,
+    convert_char: impl Fn(char) -> Option<char>,
+) -> Result<String, ConversionError> {
+    let mut out = String::with_capacity(input.len());
+    for (offset, cha) in input.char_indices() {
+        match convert_char(cha) {
+            Some(converted) => out.push(converted),
+            None if is_cjk_ideograph(cha) => return Err(ConversionError { offset, cha }),
+            None => out.push(cha),
+        }
+    }
+    Ok(out)
+}
+
+/// Returns the distinct CJK chars of the input without mapping to the target script, in the order
+/// of their first occurrence. Only chars in the CJK Unified Ideographs blocks are considered, see
+/// [is_cjk_ideograph].
+pub fn unconvertible_chars(input: &str, target: TargetScript) -> Vec<char> {
+    let mut seen = FnvHashSet::default();
+    input
+        .chars()
+        .filter(|cha| is_cjk_ideograph(*cha) && convert_char(*cha, target).is_none())
+        .filter(|cha| seen.insert(*cha))
+        .collect()
+}
+
+/// Scans until the first char that changes, and only then allocates.
+pub(crate) fn convert_cow(
+    input: &str,
+    convert_char: impl Fn(char) -> Option<char>,
+) -> Cow<'_, str> {
+    let convert = |cha: char| convert_char(cha).unwrap_or(cha);
+    for (pos, cha) in input.char_indices() {
+        let converted = convert(cha);
+        if converted == cha {
+            continue;
+        }
+        let mut out = String::with_capacity(input.len());
+        // `pos` is a byte offset, so the prefix is copied as is.
+        out.push_str(&input[..pos]);
+        out.push(converted);
+        out.extend(input[pos + cha.len_utf8()..].chars().map(convert));
+        return Cow::Owned(out);
+    }
+    Cow::Borrowed(input)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Entry {
+    pub japanese: char,
+    pub traditional_chinese: Cow<'static, [char]>,
+    pub simplified_chinese: Cow<'static, [char]>,
+}
+impl Entry {
+    /// Parses a tab separated line of the mapping table. Only the first char of every field and
+    /// candidate is used, which may be any unicode scalar value, including chars outside the BMP.
+    ///
+    /// Returns `None` if the line doesn't have 3 fields or the japanese field is empty. Use
+    /// [load_mapping_from_str] to get the reason as [ParseError]. It never panics, whatever the
+    /// input.
+    pub fn from_line(line: &str) -> Option<Self> {
+        let mut parts = line.split('\t');
+        let (Some(japanese), Some(traditional), Some(simplified), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return None; // If it doesn't match the format, we'll return None.
+        };
+
+        Some(Entry {
+            japanese: japanese.trim().chars().next()?,
+            traditional_chinese: Cow::Owned(parse_candidates(traditional)),
+            simplified_chinese: Cow::Owned(parse_candidates(simplified)),
+        })
+    }
+}
+
+/// Parses a comma separated list of candidates, `N/A` marks a missing candidate.
+fn parse_candidates(field: &str) -> Vec<char> {
+    field
+        .split(',')
+        .filter_map(|candidate| {
+            let trimmed = candidate.trim();
+            if trimmed == "N/A" {
+                None
+            } else {
+                trimmed.chars().next()
+            }
+        })
+        .collect()
+}
+
+/// Writes the entry as tab separated line of the mapping table, which can be parsed again with
+/// [Entry::from_line] or [str::parse].
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}\t", self.japanese)?;
+        write_candidates(f, &self.traditional_chinese)?;
+        f.write_char('\t')?;
+        write_candidates(f, &self.simplified_chinese)
+    }
+}
+
+fn write_candidates(f: &mut fmt::Formatter<'_>, candidates: &[char]) -> fmt::Result {
+    if candidates.is_empty() {
+        return f.write_str("N/A");
+    }
+    for (i, cha) in candidates.iter().enumerate() {
+        if i != 0 {
+            f.write_char(',')?;
+        }
+        f.write_char(*cha)?;
+    }
+    Ok(())
+}
+
+/// Parses a single line like [Entry::from_line]. The line number of the error is always 1.
+impl FromStr for Entry {
+    type Err = ParseError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        Entry::from_line(line).ok_or_else(|| mapping_table::line_error(line, 1))
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_from_line() {
+        let line = "七\t七,柒,漆\t七,柒,漆";
+        let entry = Entry::from_line(line).unwrap();
+
+        assert_eq!(entry.japanese, '七');
+        assert_eq!(entry.traditional_chinese, vec!['七', '柒', '漆']);
+        assert_eq!(entry.simplified_chinese, vec!['七', '柒', '漆']);
+
+        let line_with_na = "鰄\tN/A\tN/A";
+        assert!(Entry::from_line(line_with_na).is_some()); // Still return an Entry even if TC and SC are "N/A"
+
+        let incorrect_format_line = "just some random text";
+        assert!(Entry::from_line(incorrect_format_line).is_none()); // Should not be able to parse this line
+
+        assert!(Entry::from_line("\t學\t学").is_none());
+        assert!(Entry::from_line(" \t\t").is_none());
+        let empty_candidates = Entry::from_line("学\t\t").unwrap();
+        assert!(empty_candidates.traditional_chinese.is_empty());
+        assert!(empty_candidates.simplified_chinese.is_empty());
+    }
+
+    #[cfg(all(
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn convert_single_char() {
+        assert_eq!(convert_char_to_simplified('醫'), Some('医'));
+        assert_eq!(convert_char_to_traditional('学'), Some('學'));
+        assert_eq!(convert_char_to_japanese('學'), Some('学'));
+        assert_eq!(convert_char_to_simplified('a'), None);
+        assert_eq!(convert_char_to_traditional('a'), None);
+        assert_eq!(convert_char_to_japanese('a'), None);
+    }
+
+    #[cfg(all(
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn convert_cow() {
+        assert!(matches!(
+            convert_to_simplified_chinese_cow("hello 医生"),
+            Cow::Borrowed("hello 医生")
+        ));
+        assert!(matches!(
+            convert_to_simplified_chinese_cow("医生は醫生"),
+            Cow::Owned(out) if out == "医生は医生"
+        ));
+        assert!(matches!(
+            convert_to_traditional_chinese_cow("學"),
+            Cow::Borrowed("學")
+        ));
+        assert_eq!(convert_to_traditional_chinese_cow("学校"), "學校");
+        assert_eq!(convert_to_japanese_kanji_cow("ü學"), "ü学");
+    }
+
+    #[cfg(all(
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn needs_conversion_test() {
+        assert!(!needs_conversion(
+            "医生在学校。hello",
+            TargetScript::Simplified
+        ));
+        assert!(!needs_conversion("", TargetScript::Simplified));
+        assert!(needs_conversion("医生在學校", TargetScript::Simplified));
+        assert!(needs_conversion("医生", TargetScript::Traditional));
+        assert!(!needs_conversion("醫學", TargetScript::Traditional));
+        assert!(needs_conversion("醫學", TargetScript::Japanese));
+    }
+
+    #[cfg(all(
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn convert_segments() {
+        let input = "私は醫學の學生です";
+        let segments = convert_segments_to_simplified(input);
+        assert_eq!(segments, ["私は", "医学", "の", "学", "生です"]);
+        assert_eq!(segments.concat(), convert_to_simplified_chinese(input));
+        let input_range = input.as_bytes().as_ptr_range();
+        for segment in &segments {
+            match segment {
+                Cow::Borrowed(run) => assert!(input_range.contains(&run.as_ptr())),
+                Cow::Owned(run) => assert!(!input_range.contains(&run.as_ptr())),
+            }
+        }
+        assert!(matches!(
+            convert_segments_to_traditional("學").as_slice(),
+            [Cow::Borrowed("學")]
+        ));
+        assert_eq!(convert_segments_to_japanese("醫a"), ["医", "a"]);
+        assert!(convert_segments_to_simplified("").is_empty());
+    }
+
+    #[cfg(all(
+        feature = "direction-simplified",
+        feature = "direction-traditional",
+        not(feature = "minimal-data")
+    ))]
+    #[test]
+    fn all_candidates() {
+        assert_eq!(all_traditional_candidates('七'), &['七', '柒', '漆']);
+        assert_eq!(all_simplified_candidates('七'), &['七', '柒', '漆']);
+        assert_eq!(all_traditional_candidates('学'), &['學']);
+        assert!(all_traditional_candidates('a').is_empty());
+        assert!(all_simplified_candidates('a').is_empty());
+    }
+
+    #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+    #[test]
+    fn simplified_traditional() {
+        assert_eq!(convert_simplified_to_traditional("学"), "學");
+        assert_eq!(convert_simplified_to_traditional("医生"), "醫生");
+        assert_eq!(convert_simplified_to_traditional("學"), "學");
+        assert_eq!(convert_traditional_to_simplified("醫生"), "医生");
+        assert_eq!(convert_traditional_to_simplified("學校"), "学校");
+        assert_eq!(convert_traditional_to_simplified("abc"), "abc");
+    }
+
+    #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+    #[test]
+    fn traditional_to_simplified_many_to_one() {
+        // 發 and 髮 are also japanese kanji with their own entries
+        assert_eq!(convert_traditional_to_simplified("發髮"), "发发");
+        assert_eq!(convert_traditional_to_simplified("臺颱檯台"), "台台台台");
+        assert_eq!(convert_traditional_to_simplified("發生"), "发生");
+    }
+
+    #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+    #[test]
+    fn lookup_entry() {
+        let entry = lookup('学').unwrap();
+        assert_eq!(entry.japanese, '学');
+        assert_eq!(entry.traditional_chinese, vec!['學']);
+        assert_eq!(entry.simplified_chinese, vec!['学']);
+        assert_eq!(lookup('學').unwrap().japanese, '学');
+        assert_eq!(lookup('医').unwrap().japanese, '医');
+        assert_eq!(lookup('醫').unwrap().japanese, '医');
+        // Further candidates are keys too, '柒' is the second candidate of '七' and '漆'
+        assert_eq!(lookup('柒').unwrap().japanese, '七');
+        assert!(lookup('a').is_none());
+    }
+
+    #[cfg(feature = "direction-japanese")]
+    #[test]
+    fn detect_script_test() {
+        assert_eq!(detect_script("醫生"), ScriptGuess::TraditionalChinese);
+        assert_eq!(detect_script("医生"), ScriptGuess::SimplifiedChinese);
+        assert_eq!(detect_script("医者は沢に行きます"), ScriptGuess::Japanese);
+        assert_eq!(detect_script("医醫"), ScriptGuess::Mixed);
+        assert_eq!(detect_script("生"), ScriptGuess::Unknown);
+        assert_eq!(detect_script("hello"), ScriptGuess::Unknown);
+        assert_eq!(detect_script(""), ScriptGuess::Unknown);
+    }
+
+    #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+    #[test]
+    fn known_chars() {
+        assert!(is_known_kanji('学'));
+        assert!(!is_known_kanji('學'));
+        assert!(is_known_simplified('学'));
+        assert!(!is_known_simplified('學'));
+        assert!(is_known_traditional('學'));
+        assert!(!is_known_traditional('学'));
+        assert!(!is_known_kanji('a'));
+        assert!(!is_known_simplified('a'));
+        assert!(!is_known_traditional('a'));
+    }
+
+    #[cfg(all(
+        feature = "serde",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn entry_serde_roundtrip() {
+        let entry = Entry::from_line("七\t七,柒,漆\tN/A").unwrap();
+        let json = serde_json::to_string(&entry).unwrap();
+        let deserialized: Entry = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.japanese, '七');
+        assert_eq!(deserialized.traditional_chinese, vec!['七', '柒', '漆']);
+        assert!(deserialized.simplified_chinese.is_empty());
+
+        let mapping: FnvHashMap<char, Entry> = serde_json::from_str(&dump_mapping_json()).unwrap();
+        assert_eq!(mapping.len(), get_hashmap().len());
+        assert_eq!(mapping[&'学'].traditional_chinese, vec!['學']);
+    }
+
+    #[cfg(all(
+        feature = "serde",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn export_json_test() {
+        let json = export_json();
+        assert_eq!(json, export_json());
+        let exported: Vec<serde_json::Value> = serde_json::from_str(&json).unwrap();
+        assert_eq!(exported.len(), entries().count());
+        let gaku = exported.iter().find(|entry| entry["jp"] == "学").unwrap();
+        assert_eq!(gaku["trad"], serde_json::json!(["學"]));
+        assert_eq!(gaku["simp"], serde_json::json!(["学"]));
+        let japanese: Vec<&str> = exported
+            .iter()
+            .map(|entry| entry["jp"].as_str().unwrap())
+            .collect();
+        assert!(japanese.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+    #[test]
+    fn entry_eq_hash() {
+        let parsed = Entry::from_line("医\t醫,毉\t医").unwrap();
+        let borrowed = Entry {
+            japanese: '医',
+            traditional_chinese: Cow::Borrowed(&['醫', '毉']),
+            simplified_chinese: Cow::Borrowed(&['医']),
+        };
+        assert_eq!(parsed, borrowed);
+        assert_eq!(lookup('医'), Some(&borrowed));
+        assert_ne!(parsed, Entry::from_line("医\t醫\t医").unwrap());
+        let set: FnvHashSet<Entry> = [parsed, borrowed].into_iter().collect();
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn entry_display_roundtrip() {
+        for line in ["医\t醫,毉\t医", "丼\tN/A\tN/A", "学\t學\t学"] {
+            let entry: Entry = line.parse().unwrap();
+            assert_eq!(entry.to_string(), line);
+        }
+        let entry: Entry = "丼\tN/A\tN/A".parse().unwrap();
+        assert!(entry.traditional_chinese.is_empty());
+        assert_eq!(
+            "学\t學".parse::<Entry>().unwrap_err(),
+            ParseError::InvalidFieldCount { line: 1, count: 2 }
+        );
+        assert_eq!(
+            " \t學\t学".parse::<Entry>().unwrap_err(),
+            ParseError::MissingJapanese { line: 1 }
+        );
+    }
+
+    #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+    #[test]
+    fn canonicalize_test() {
+        assert_eq!(canonicalize_str("醫學"), canonicalize_str("医学"));
+        assert_eq!(canonicalize_str("醫學"), "医学");
+        assert_eq!(canonicalize('体'), canonicalize('體'));
+        assert_eq!(canonicalize_str("abc"), "abc");
+    }
+
+    #[cfg(all(feature = "direction-japanese", feature = "direction-simplified"))]
+    #[test]
+    fn normalize_japanese_test() {
+        assert_eq!(normalize_japanese("學國體氣"), "学国体気");
+        assert_eq!(normalize_japanese("櫻澤さん"), "桜沢さん");
+        assert_eq!(normalize_japanese("学校"), "学校");
+        // Simplified chinese is not an old form
+        assert_eq!(normalize_japanese("对"), "对");
+    }
+
+    #[cfg(all(
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn confidence() {
+        assert_eq!(
+            convert_char_with_confidence('学', TargetScript::Traditional),
+            Some(('學', 1.0))
+        );
+        assert_eq!(
+            convert_char_with_confidence('医', TargetScript::Traditional),
+            Some(('醫', 0.5))
+        );
+        assert_eq!(
+            convert_char_with_confidence('醫', TargetScript::Japanese),
+            Some(('医', 1.0))
+        );
+        assert_eq!(
+            convert_char_with_confidence('a', TargetScript::Simplified),
+            None
+        );
+    }
+
+    #[cfg(all(feature = "direction-traditional", not(feature = "minimal-data")))]
+    #[test]
+    fn confidence_of_unchanged_candidate() {
+        assert_eq!(all_traditional_candidates('柒').len(), 3);
+        assert_eq!(
+            convert_char_with_confidence('柒', TargetScript::Traditional),
+            Some(('柒', 1.0))
+        );
+    }
+
+    #[cfg(feature = "direction-japanese")]
+    #[test]
+    fn japanese_candidates_test() {
+        assert_eq!(japanese_candidates('插'), vec!['扱', '挿']);
+        assert_eq!(japanese_candidates('醫'), vec!['医']);
+        assert!(japanese_candidates('a').is_empty());
+    }
+
+    #[cfg(all(
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn is_ambiguous_test() {
+        // 医 has the traditional candidates 醫 and 毉.
+        assert!(is_ambiguous('医', TargetScript::Traditional));
+        // 舍 is the traditional form of 舎 and the simplified form of 捨.
+        assert_eq!(all_simplified_candidates('舍'), &['舍']);
+        assert!(is_ambiguous('舍', TargetScript::Simplified));
+        assert!(!is_ambiguous('学', TargetScript::Simplified));
+        assert!(!is_ambiguous('医', TargetScript::Japanese));
+        assert!(!is_ambiguous('a', TargetScript::Traditional));
+    }
+
+    #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+    #[test]
+    fn convert_char_nth() {
+        assert_eq!(convert_char_to_traditional_nth('医', 0), Some('醫'));
+        assert_eq!(convert_char_to_traditional_nth('医', 1), Some('毉'));
+        assert_eq!(convert_char_to_traditional_nth('医', 2), None);
+        assert_eq!(convert_char_to_simplified_nth('醫', 0), Some('医'));
+        assert_eq!(convert_char_to_simplified_nth('a', 0), None);
+    }
+
+    #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+    #[test]
+    fn context_changes_candidate() {
+        let bigrams = [('颱', '風'), ('檯', '燈')];
+        assert_eq!(
+            convert_with_context("台风和台灯", TargetScript::Traditional, 1, &bigrams),
+            "颱風和檯燈"
+        );
+        // Outside of the window
+        assert_eq!(
+            convert_with_context("台的风", TargetScript::Traditional, 1, &bigrams),
+            "台的風"
+        );
+        assert_eq!(
+            convert_with_context("台的风", TargetScript::Traditional, 2, &bigrams),
+            "颱的風"
+        );
+    }
+
+    #[cfg(all(feature = "direction-japanese", feature = "direction-traditional"))]
+    #[test]
+    fn convert_detailed_test() {
+        // 医 has the traditional candidates 醫 and 毉
+        assert_eq!(
+            convert_detailed("a医学", TargetScript::Traditional),
+            Conversion {
+                output: "a醫學".to_string(),
+                lossy: true,
+                ambiguous_positions: vec![1],
+            }
+        );
+        let conversion = convert_detailed("醫學", TargetScript::Japanese);
+        assert_eq!(conversion.output, "医学");
+        assert!(!conversion.lossy);
+        assert!(conversion.ambiguous_positions.is_empty());
+    }
+
+    #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+    #[test]
+    fn convert_detailed_matches_confidence() {
+        // 柒 is already one of its traditional candidates, so it is kept with a confidence of 1.0
+        assert_eq!(
+            convert_char_with_confidence('柒', TargetScript::Traditional),
+            Some(('柒', 1.0))
+        );
+        assert!(!convert_detailed("柒", TargetScript::Traditional).lossy);
+
+        let input = "a柒医学台发壹體萬";
+        for target in [TargetScript::Traditional, TargetScript::Simplified] {
+            let conversion = convert_detailed(input, target);
+            assert_eq!(conversion.output, convert(input, target));
+            for (pos, cha) in input.char_indices() {
+                let ambiguous =
+                    matches!(convert_char_with_confidence(cha, target), Some((_, c)) if c < 1.0);
+                assert_eq!(
+                    conversion.ambiguous_positions.contains(&pos),
+                    ambiguous,
+                    "{cha} to {target:?}"
+                );
+            }
+        }
+    }
+
+    #[cfg(all(
+        feature = "direction-simplified",
+        feature = "direction-traditional",
+        not(feature = "minimal-data")
+    ))]
+    #[test]
+    fn convert_char_all() {
+        assert_eq!(convert_char_all_simplified('壹'), vec!['一', '壹']);
+        assert_eq!(convert_char_all_simplified('醫'), vec!['医']);
+        assert_eq!(convert_char_all_traditional('医'), vec!['醫', '毉']);
+        assert!(convert_char_all_simplified('a').is_empty());
+    }
+
+    #[cfg(all(
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn convert_counted() {
+        assert_eq!(
+            convert_to_traditional_counted("学校a學"),
+            ("學校a學".to_string(), 1)
+        );
+        assert_eq!(convert_to_simplified_counted("abc"), ("abc".to_string(), 0));
+        assert_eq!(convert_to_japanese_counted("學醫"), ("学医".to_string(), 2));
+    }
+
+    #[cfg(all(
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn convert_report() {
+        assert_eq!(
+            convert_to_simplified_chinese_report("醫a生ü"),
+            ("医a生ü".to_string(), vec![(3, 'a'), (7, 'ü')])
+        );
+        assert_eq!(
+            convert_to_traditional_chinese_report("学"),
+            ("學".to_string(), vec![])
+        );
+        assert_eq!(
+            convert_to_japanese_kanji_report("學!"),
+            ("学!".to_string(), vec![(3, '!')])
+        );
+    }
+
+    #[cfg(all(
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn convert_to_target() {
+        assert_eq!(convert("醫生", TargetScript::Simplified), "医生");
+        assert_eq!(convert("学生", TargetScript::Traditional), "學生");
+        assert_eq!(convert("學生", TargetScript::Japanese), "学生");
+        assert_eq!(convert_char('學', TargetScript::Japanese), Some('学'));
+        assert_eq!(convert_char('a', TargetScript::Simplified), None);
+    }
+
+    #[cfg(all(
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn str_ext() {
+        assert_eq!("醫生".to_simplified_chinese(), "医生");
+        assert_eq!("学生".to_traditional_chinese(), "學生");
+        assert_eq!(String::from("學生").to_japanese_kanji(), "学生");
+    }
+
+    #[cfg(all(
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn convert_with_fallback() {
+        let mut misses = Vec::new();
+        let out = convert_to_simplified_with("醫a生b", |cha| {
+            misses.push(cha);
+            Some('?')
+        });
+        assert_eq!(out, "医?生?");
+        assert_eq!(misses, vec!['a', 'b']);
+
+        assert_eq!(convert_to_traditional_with("学 校", |_| None), "學校");
+        assert_eq!(convert_to_japanese_with("學.", |_| Some('。')), "学。");
+    }
+
+    #[cfg(all(
+        feature = "direction-simplified",
+        feature = "direction-traditional",
+        not(feature = "minimal-data")
+    ))]
+    #[test]
+    fn generated_table_matches_runtime_parsing() {
+        let kanji_list = get_kanji_list();
+        let table = MappingTable::from_entries(
+            include_str!("../kanji_mapping_table.txt")
+                .lines()
+                .filter_map(Entry::from_line),
+        )
+        .retain(|entry| kanji_list.contains(&entry.japanese));
+        let maps = [
+            (table.japanese_map(), get_hashmap()),
+            (table.simplified_map(), get_simplified_hashmap()),
+            (table.traditional_map(), get_traditional_hashmap()),
+        ];
+        for (runtime, generated) in maps {
+            assert_eq!(runtime.len(), generated.len());
+            for ((key1, entry1), (key2, entry2)) in runtime.iter().zip(generated.iter()) {
+                assert_eq!(key1, key2);
+                assert_eq!(entry1.japanese, entry2.japanese);
+                assert_eq!(entry1.traditional_chinese, entry2.traditional_chinese);
+                assert_eq!(entry1.simplified_chinese, entry2.simplified_chinese);
+            }
+        }
+    }
+
+    #[cfg(all(
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional",
+        feature = "minimal-data"
+    ))]
+    #[test]
+    fn minimal_data_keeps_conversions() {
+        let kanji_list = get_kanji_list();
+        let table = MappingTable::from_entries(
+            include_str!("../kanji_mapping_table.txt")
+                .lines()
+                .filter_map(Entry::from_line),
+        )
+        .retain(|entry| kanji_list.contains(&entry.japanese));
+        let chars = table
+            .japanese_map()
+            .keys()
+            .chain(table.simplified_map().keys())
+            .chain(table.traditional_map().keys())
+            .copied()
+            .collect::<Vec<_>>();
+        for cha in chars {
+            for target in [
+                TargetScript::Japanese,
+                TargetScript::Traditional,
+                TargetScript::Simplified,
+            ] {
+                assert_eq!(
+                    convert_char(cha, target),
+                    table.convert_char(cha, target),
+                    "{cha} to {target:?}"
+                );
+            }
+        }
+        // 壹 is left out as candidate of 一, it resolves to its own entry
+        assert_eq!(all_traditional_candidates('一'), &['一']);
+        assert_eq!(convert_char_to_traditional('壹'), Some('壹'));
+        assert_eq!(convert_char_to_simplified('壹'), Some('壹'));
+        // 柒 resolves to 七, so it stays a candidate of it
+        assert_eq!(all_traditional_candidates('七'), &['七', '柒']);
+    }
+
+    #[cfg(all(feature = "direction-japanese", feature = "direction-simplified"))]
+    #[test]
+    fn per_direction_maps() {
+        // "插" is the first simplified candidate of "扱", but the first traditional candidate of
+        // "挿". As traditional char it is "挿" in japanese.
+        assert_eq!(convert_char_to_japanese('插'), Some('挿'));
+        assert_eq!(convert_traditional_to_simplified("插"), "插");
+        // Same for "舍", which is the traditional form of "舎"
+        assert_eq!(convert_char_to_japanese('舍'), Some('舎'));
+        assert_eq!(lookup('舍').unwrap().japanese, '舎');
+        // Already simplified chars are not converted further
+        assert_eq!(convert_char_to_simplified('插'), Some('插'));
+    }
+
+    #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+    #[test]
+    fn entries_are_shared_between_keys() {
+        let entry = lookup('学').unwrap();
+        assert!(std::ptr::eq(entry, lookup('學').unwrap()));
+        assert!(std::ptr::eq(entry, get_hashmap().get(&'学').unwrap()));
+        assert!(std::ptr::eq(
+            entry,
+            get_simplified_hashmap().get(&'学').unwrap()
+        ));
+        assert!(std::ptr::eq(
+            entry,
+            get_traditional_hashmap().get(&'學').unwrap()
+        ));
+
+        let table = load_mapping_from_str("学\t學\t学\n").unwrap();
+        let entry = table.lookup('学').unwrap();
+        assert!(std::ptr::eq(entry, table.lookup('學').unwrap()));
+        assert_eq!(table.entries().len(), 1);
+    }
+
+    #[cfg(all(
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn convert_many() {
+        assert_eq!(
+            convert_many_to_simplified(&["醫生", "", "abc", "學校"]),
+            vec!["医生", "", "abc", "学校"]
+        );
+        assert_eq!(convert_many_to_traditional(&["学", "医"]), vec!["學", "醫"]);
+        assert_eq!(convert_many_to_japanese(&["學", "醫"]), vec!["学", "医"]);
+        assert!(convert_many_to_simplified(&[]).is_empty());
+    }
+
+    #[cfg(all(
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn try_convert() {
+        assert_eq!(
+            try_convert_to_simplified("醫生, a 學校。").as_deref(),
+            Ok("医生, a 学校。")
+        );
+        // 𠮟 is outside of the mapping table
+        let err = try_convert_to_simplified("醫𠮟").unwrap_err();
+        assert_eq!(
+            err,
+            ConversionError {
+                offset: 3,
+                cha: '𠮟'
+            }
+        );
+        assert_eq!(err.to_string(), "no mapping for '𠮟' at byte offset 3");
+        assert_eq!(try_convert_to_traditional("学").as_deref(), Ok("學"));
+        assert_eq!(try_convert_to_japanese("學").as_deref(), Ok("学"));
+    }
+
+    #[cfg(all(
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn convert_diff() {
+        assert_eq!(
+            convert_diff_to_simplified("醫生 ab學校"),
+            vec![
+                DiffSpan::Changed {
+                    from: '醫',
+                    to: '医'
+                },
+                DiffSpan::Unchanged("生 ab".to_string()),
+                DiffSpan::Changed {
+                    from: '學',
+                    to: '学'
+                },
+                DiffSpan::Unchanged("校".to_string()),
+            ]
+        );
+        assert_eq!(
+            convert_diff_to_traditional("abc"),
+            vec![DiffSpan::Unchanged("abc".to_string())]
+        );
+        assert_eq!(
+            convert_diff_to_japanese("學醫"),
+            vec![
+                DiffSpan::Changed {
+                    from: '學',
+                    to: '学'
+                },
+                DiffSpan::Changed {
+                    from: '醫',
+                    to: '医'
+                },
+            ]
+        );
+        assert!(convert_diff_to_simplified("").is_empty());
+    }
+
+    #[cfg(all(
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn convert_mapped() {
+        assert_eq!(
+            convert_to_simplified_mapped("醫a生"),
+            vec![('醫', '医'), ('a', 'a'), ('生', '生')]
+        );
+        assert_eq!(convert_to_traditional_mapped("学"), vec![('学', '學')]);
+        assert_eq!(convert_to_japanese_mapped("學"), vec![('學', '学')]);
+        assert!(convert_to_japanese_mapped("").is_empty());
+    }
+
+    #[cfg(all(
+        feature = "compat-ideographs",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn compatibility_ideographs() {
+        // U+F914 is the compatibility ideograph of 樂 U+6A02
+        assert_eq!(normalize_compatibility_ideograph('\u{F914}'), '\u{6A02}');
+        assert_eq!(normalize_compatibility_ideograph('学'), '学');
+        assert_eq!(convert_char_to_simplified('\u{F914}'), None);
+        assert_eq!(convert_char_to_simplified('\u{6A02}'), Some('乐'));
+    }
+
+    #[cfg(feature = "direction-simplified")]
+    #[test]
+    fn reporting_kokuji() {
+        // 働 has the chinese form 动 in the mapping table, 𠮟 is not in the kanji list
+        assert_eq!(
+            convert_to_simplified_reporting_kokuji("働く峠と畑、峠𠮟"),
+            ("动く峠と畑、峠𠮟".to_string(), vec!['峠', '畑'])
+        );
+    }
+
+    #[cfg(all(
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn all_convertible() {
+        assert!(is_all_convertible(
+            "醫生、學校です",
+            TargetScript::Simplified
+        ));
+        assert!(!is_all_convertible("醫𠮟", TargetScript::Simplified));
+        assert!(is_all_convertible("abc。", TargetScript::Japanese));
+        assert!(is_all_convertible("", TargetScript::Traditional));
+    }
+
+    #[cfg(all(
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn coverage_test() {
+        assert_eq!(coverage(&['学', '医'], TargetScript::Traditional), 1.0);
+        // 醫 is not in the kanji list
+        assert_eq!(
+            coverage(&['学', '醫', 'a', '校'], TargetScript::Simplified),
+            0.5
+        );
+        assert_eq!(coverage(&[], TargetScript::Japanese), 0.0);
+    }
+
+    #[cfg(all(feature = "direction-japanese", feature = "direction-simplified"))]
+    #[test]
+    fn unconvertible() {
+        assert_eq!(
+            unconvertible_chars("𠮟a醫𩸽。𠮟", TargetScript::Simplified),
+            vec!['𠮟', '𩸽']
+        );
+        assert!(unconvertible_chars("學校!", TargetScript::Japanese).is_empty());
+    }
+
+    #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+    #[test]
+    fn decomposed_input() {
+        // NFD of "學校が" decomposes only the kana
+        assert_eq!(
+            convert_to_simplified_chinese("學校か\u{3099}"),
+            "学校か\u{3099}"
+        );
+    }
+
+    #[cfg(all(
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn convert_into_reused_buffer() {
+        let mut out = String::new();
+        convert_to_simplified_into("醫生和學校的老師", &mut out);
+        assert_eq!(out, "医生和学校的老师");
+        let capacity = out.capacity();
+        convert_to_simplified_into("學校", &mut out);
+        assert_eq!(out, "学校");
+        assert_eq!(out.capacity(), capacity);
+        convert_to_traditional_into("学", &mut out);
+        assert_eq!(out, "學");
+        convert_to_japanese_into("", &mut out);
+        assert_eq!(out, "");
+    }
+
+    #[cfg(all(
+        feature = "rayon",
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn par_convert_many() {
+        let inputs: Vec<String> = (0..1000).map(|i| format!("{i}醫生學校{i}")).collect();
+        let inputs: Vec<&str> = inputs.iter().map(|input| input.as_str()).collect();
+        assert_eq!(
+            par_convert_many_to_simplified(&inputs),
+            convert_many_to_simplified(&inputs)
+        );
+        assert_eq!(
+            par_convert_many_to_traditional(&inputs),
+            convert_many_to_traditional(&inputs)
+        );
+        assert_eq!(
+            par_convert_many_to_japanese(&inputs),
+            convert_many_to_japanese(&inputs)
+        );
+    }
+
+    #[cfg(all(
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn further_candidates_are_keys() {
+        // "台" has the traditional candidates "台", "檯", "臺", "颱"
+        assert_eq!(convert_char_to_japanese('颱'), Some('台'));
+        assert_eq!(convert_char_to_japanese('臺'), Some('台'));
+        assert_eq!(convert_to_simplified_chinese("颱風"), "台风");
+        assert_eq!(convert_traditional_to_simplified("颱"), "台");
+        assert!(is_known_traditional('颱'));
+        // Already traditional chars are kept
+        assert_eq!(convert_char_to_traditional('颱'), Some('颱'));
+        assert_eq!(convert_char_to_traditional('台'), Some('台'));
+    }
+
+    #[cfg(all(
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn further_candidates_without_own_entry() {
+        // Their own lines are not in the kanji list, so they resolve through the entry that lists
+        // them as further traditional candidate
+        for (cha, japanese) in [('體', '体'), ('臺', '台'), ('颱', '台'), ('萬', '万')] {
+            assert_eq!(lookup(cha).unwrap().japanese, japanese);
+            assert_eq!(convert_char_to_japanese(cha), Some(japanese));
+            assert_eq!(convert_char_to_simplified(cha), Some(japanese));
+            assert_eq!(convert_char_to_traditional(cha), Some(cha));
+        }
+        assert_eq!(convert_to_japanese_kanji("體育"), "体育");
+        assert_eq!(convert_to_simplified_chinese("臺灣"), "台湾");
+        assert_eq!(convert_to_japanese_kanji("颱風"), "台風");
+        assert_eq!(convert_to_simplified_chinese("一萬"), "一万");
+        assert_eq!(convert_to_traditional_chinese("體臺颱萬"), "體臺颱萬");
+        // "托" is a further traditional candidate of "拓", but resolves through its own entry "託",
+        // where it is the first simplified candidate
+        assert_eq!(lookup('托').unwrap().japanese, '託');
+        assert_eq!(convert_char_to_japanese('托'), Some('託'));
+    }
+
+    #[cfg(all(feature = "direction-japanese", feature = "direction-simplified"))]
+    #[test]
+    fn supplementary_plane_chars() {
+        // The embedded table has no chars outside the BMP, but custom tables may
+        let entry = Entry::from_line("𠮟\t叱,𠮟\t叱").unwrap();
+        assert_eq!(entry.japanese, '𠮟');
+        assert_eq!(entry.traditional_chinese, vec!['叱', '𠮟']);
+
+        let table = load_mapping_from_str("𠮟\t叱\t叱\n").unwrap();
+        assert_eq!(table.convert_to_simplified_chinese("a𠮟b"), "a叱b");
+        assert_eq!(table.convert_to_japanese_kanji("叱"), "𠮟");
+
+        // Passed through unchanged by the embedded table
+        assert_eq!(convert_to_simplified_chinese("醫𠮟𩸽生"), "医𠮟𩸽生");
+        assert_eq!(convert_to_japanese_kanji_cow("𠮟學"), "𠮟学");
+        assert_eq!(
+            convert_to_simplified_chinese_report("𠮟醫"),
+            ("𠮟医".to_string(), vec![(0, '𠮟')])
+        );
+    }
+
+    #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+    #[test]
+    fn filter_entries() {
+        let multiple = entries_where(|entry| entry.traditional_chinese.len() > 1);
+        assert_eq!(multiple.len(), stats().multiple_traditional);
+        assert!(multiple.iter().any(|entry| entry.japanese == '医'));
+        assert!(multiple.iter().all(|entry| entry.japanese != '学'));
+    }
+
+    #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+    #[test]
+    fn embedded_stats() {
+        let stats = stats();
+        assert_eq!(stats.entries, entries().count());
+        assert!(stats.multiple_traditional > 0);
+        assert!(stats.identical > 0);
+        assert!(stats.identical < stats.entries);
+    }
+
+    /// Kanji that don't round-trip from japanese to simplified and back, because the simplified form
+    /// resolves to another entry, e.g. 幾 -> 几, which is itself a kanji.
+    #[cfg(all(feature = "direction-japanese", feature = "direction-simplified"))]
+    const NOT_ROUND_TRIPPING: &str = "彐刂気扌阝亻礻灬忄衤氵幾機様釆葉広週係準歴製働隻児髪裏幹雲闘\
+        埼拠昇誌複採竜併扱捨衝咲闇麺氷菓脇繋嬢荘丼箇醜碁隷穀穫慄瞭";
+
+    #[cfg(all(feature = "direction-japanese", feature = "direction-simplified"))]
+    #[test]
+    fn round_trip_japanese_simplified() {
+        let not_round_tripping: String = kanji_list_ordered()
+            .iter()
+            .filter(|cha| {
+                let simplified = convert_to_simplified_chinese(&cha.to_string());
+                convert_to_japanese_kanji(&simplified) != cha.to_string()
+            })
+            .collect();
+        assert_eq!(not_round_tripping, NOT_ROUND_TRIPPING);
+    }
+
+    #[test]
+    fn owned_copies() {
+        let mut mapping = clone_mapping();
+        assert_eq!(mapping.len(), get_hashmap().len());
+        mapping.remove(&'学');
+        assert!(get_hashmap().contains_key(&'学'));
+
+        let mut kanji_list = clone_kanji_list();
+        kanji_list.clear();
+        assert!(is_known_kanji('学'));
+    }
+
+    #[test]
+    fn kanji_list_order() {
+        let ordered = kanji_list_ordered();
+        assert_eq!(ordered.len(), get_kanji_list().len());
+        assert_eq!(&ordered[..3], &['一', '丨', '口']);
+    }
+
+    #[test]
+    fn entries_are_unique() {
+        let japanese: FnvHashSet<char> = entries().map(|entry| entry.japanese).collect();
+        assert_eq!(entries().count(), japanese.len());
+        assert!(entries().any(|entry| entry.japanese == '医'));
+    }
+
+    #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+    #[test]
+    fn export_csv_test() {
+        let csv = export_csv();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("japanese,traditional,simplified"));
+        assert_eq!(lines.clone().count(), entries().count());
+        assert!(lines.clone().any(|line| line == "医,\"醫,毉\",医"));
+        let japanese: Vec<char> = lines.map(|line| line.chars().next().unwrap()).collect();
+        assert!(japanese.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+    #[test]
+    fn normalize_width() {
+        assert_eq!(to_half_width('１'), '1');
+        assert_eq!(to_half_width('Ａ'), 'A');
+        assert_eq!(to_half_width('～'), '~');
+        assert_eq!(to_half_width('\u{3000}'), ' ');
+        assert_eq!(to_half_width('学'), '学');
+        assert_eq!(
+            convert_normalize_width("學校\u{3000}２０２４", TargetScript::Simplified),
+            "学校 2024"
+        );
+        // Off by default
+        assert_eq!(
+            convert("學校\u{3000}２０２４", TargetScript::Simplified),
+            "学校\u{3000}２０２４"
+        );
+    }
+
+    #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+    #[test]
+    fn related_forms_test() {
+        let forms = related_forms('医').unwrap();
+        assert_eq!(forms.japanese, '医');
+        assert_eq!(forms.traditional, vec!['醫', '毉']);
+        assert_eq!(related_forms('醫'), Some(forms));
+        assert_eq!(related_forms('a'), None);
+    }
+
+    #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+    #[test]
+    fn relation_path_test() {
+        assert!(are_related('学', '學'));
+        assert!(are_related('學', '学'));
+        assert_eq!(relation_path('學', '学'), Some(vec!['學', '学']));
+        assert_eq!(relation_path('学', '学'), Some(vec!['学']));
+        // 插 connects the entries of 扱 and 挿
+        assert_eq!(relation_path('扱', '挿'), Some(vec!['扱', '插', '挿']));
+        assert!(!are_related('学', '医'));
+        assert!(!are_related('a', 'a'));
+        assert_eq!(relation_path('学', 'a'), None);
+    }
+
+    #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+    #[test]
+    fn conversion_stats() {
+        let mut stats = ConversionStats::new();
+        stats.observe("醫學", TargetScript::Simplified);
+        stats.observe("學校a", TargetScript::Simplified);
+        stats.observe("学", TargetScript::Traditional);
+        assert_eq!(
+            stats.report(),
+            [('學', '学', 2), ('学', '學', 1), ('醫', '医', 1)]
+        );
+
+        let handles: Vec<_> = ["醫生", "醫者"]
+            .into_iter()
+            .map(|text| {
+                std::thread::spawn(move || {
+                    let mut stats = ConversionStats::new();
+                    stats.observe(text, TargetScript::Simplified);
+                    stats
+                })
+            })
+            .collect();
+        for handle in handles {
+            stats.merge(&handle.join().unwrap());
+        }
+        assert_eq!(stats.report()[0], ('醫', '医', 3));
+        assert_eq!(stats.report().len(), 3);
+    }
+
+    #[cfg(all(
+        feature = "direction-simplified",
+        feature = "direction-traditional",
+        not(feature = "minimal-data")
+    ))]
+    #[test]
+    fn shared_candidate_lists() {
+        // Identical candidate lists in both directions and across entries
+        assert_eq!(all_traditional_candidates('七'), &['七', '柒', '漆']);
+        assert_eq!(all_simplified_candidates('七'), &['七', '柒', '漆']);
+        assert_eq!(all_simplified_candidates('扱'), &['插']);
+        assert_eq!(all_simplified_candidates('挿'), &['插']);
+        assert_eq!(all_traditional_candidates('扱'), &['扱', '插']);
+        assert_eq!(all_traditional_candidates('挿'), &['插']);
+
+        // The build script interns them, so they are the same slice
+        let same = |a: &[char], b: &[char]| core::ptr::eq(a, b);
+        assert!(same(
+            all_traditional_candidates('七'),
+            all_simplified_candidates('七')
+        ));
+        assert!(same(
+            all_simplified_candidates('扱'),
+            all_simplified_candidates('挿')
+        ));
+        assert!(same(
+            all_simplified_candidates('挿'),
+            all_traditional_candidates('挿')
+        ));
+        assert!(!same(
+            all_traditional_candidates('扱'),
+            all_traditional_candidates('挿')
+        ));
+    }
+
+    #[test]
+    fn widen_katakana_test() {
+        assert_eq!(widen_katakana("ｶﾞｯｺｳ ﾊﾟﾋﾞｳﾞｧ"), "ガッコウ パビヴァ");
+        assert_eq!(widen_katakana("｢ｱ｣｡ﾞﾝﾟ"), "「ア」。゛ン゜");
+        assert_eq!(to_full_width_katakana('ｰ'), 'ー');
+        assert_eq!(to_full_width_katakana('a'), 'a');
+    }
+
+    #[cfg(all(
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn pairs_test() {
+        for pairs in [simplified_pairs(), traditional_pairs(), japanese_pairs()] {
+            assert!(pairs.windows(2).all(|pair| pair[0].0 < pair[1].0));
+            assert!(pairs.iter().all(|(source, converted)| source != converted));
+        }
+        let simplified = simplified_pairs();
+        assert!(simplified.contains(&('醫', '医')));
+        assert!(simplified.contains(&('學', '学')));
+        assert!(!simplified.iter().any(|(source, _)| *source == '学'));
+        assert!(traditional_pairs().contains(&('学', '學')));
+        assert!(japanese_pairs().contains(&('學', '学')));
+    }
+
+    #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+    #[test]
+    fn convert_to_both_chinese_test() {
+        for text in ["医学と芸術の国", "醫學", "", "abc 壱万円"] {
+            assert_eq!(
+                convert_to_both_chinese(text),
+                (
+                    convert_to_simplified_chinese(text),
+                    convert_to_traditional_chinese(text)
+                )
+            );
+        }
+        assert_eq!(
+            convert_to_both_chinese("医学"),
+            ("医学".to_string(), "醫學".to_string())
+        );
+    }
+
+    #[cfg(feature = "direction-simplified")]
+    #[test]
+    fn dataset_version_test() {
+        let version = dataset_version();
+        assert!(version.starts_with("fnv1a64:"));
+        assert_eq!(version.len(), "fnv1a64:".len() + 16);
+        let raw = raw_mapping_table();
+        assert!(!raw.is_empty());
+        let table = load_mapping_from_str(raw).unwrap();
+        assert_eq!(table.convert_to_simplified_chinese("醫生"), "医生");
+    }
+
+    #[test]
+    fn warm_up_test() {
+        let handles: Vec<_> = (0..4).map(|_| std::thread::spawn(warm_up)).collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        warm_up();
+        assert!(is_known_kanji('学'));
+    }
+
+    #[cfg(not(any(feature = "direction-traditional", feature = "direction-japanese")))]
+    #[test]
+    fn without_traditional_direction() {
+        assert!(!is_known_traditional('學'));
+    }
+
+    #[cfg(not(any(feature = "direction-simplified", feature = "direction-japanese")))]
+    #[test]
+    fn without_simplified_direction() {
+        assert!(!is_known_simplified('医'));
+    }
+
+    #[cfg(all(
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn ideographic_zero() {
+        let date = "二〇二四年";
+        assert_eq!(convert_to_simplified_chinese(date), date);
+        assert_eq!(convert_to_traditional_chinese(date), date);
+        assert_eq!(convert_to_japanese_kanji(date), date);
+        assert_eq!(lookup('〇'), None);
+        let converter = ConverterBuilder::new().normalize_daiji(true).build();
+        assert_eq!(converter.convert("壱〇"), "一〇");
+    }
+
+    #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+    #[test]
+    fn entry_id_test() {
+        let id = entry_id('学').unwrap();
+        assert_eq!(entry_id('學'), Some(id));
+        assert_eq!(entry_by_id(id).unwrap().japanese, '学');
+        assert_ne!(entry_id('医'), Some(id));
+        assert_eq!(entry_id('a'), None);
+        assert_eq!(entry_by_id(usize::MAX), None);
+        for entry in entries() {
+            assert_eq!(entry_by_id(entry_id(entry.japanese).unwrap()), Some(entry));
+        }
+    }
+
+    #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+    #[test]
+    fn convert_mixed_scripts_to_simplified_test() {
+        // japanese, traditional and simplified forms
+        assert_eq!(convert_to_simplified_chinese("医醫医"), "医医医");
+        assert_eq!(convert_to_simplified_chinese("発發发"), "发发发");
+        assert_eq!(convert_to_simplified_chinese("国國国"), "国国国");
+        assert_eq!(
+            convert_to_simplified_chinese("日本の医学と臺灣的醫學和中国的医学"),
+            "日本の医学と台湾的医学和中国的医学"
+        );
+        assert_eq!(convert_to_simplified_chinese("芸"), "芸");
+        // further traditional candidates of an entry
+        assert_eq!(convert_to_simplified_chinese("体體体"), "体体体");
+        assert_eq!(convert_to_simplified_chinese("台臺台"), "台台台");
     }
 
+    #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
     #[test]
     fn to_simplified_test() {
         assert_eq!(convert_to_simplified_chinese("醫生"), "医生");
     }
 
+    #[cfg(feature = "direction-japanese")]
     #[test]
     fn to_japanese() {
         assert_eq!(convert_to_japanese_kanji("一"), "一");
@@ -189,12 +2940,14 @@ mod tests {
         assert_eq!(convert_to_japanese_kanji("學"), "学");
     }
 
+    #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
     #[test]
     fn to_simplified_chinese() {
         assert_eq!(convert_to_simplified_chinese("学"), "学");
         assert_eq!(convert_to_simplified_chinese("學"), "学");
     }
 
+    #[cfg(feature = "direction-traditional")]
     #[test]
     fn to_traditional_chinese() {
         assert_eq!(convert_to_traditional_chinese("学"), "學");
@@ -0,0 +1,86 @@
+use std::collections::BTreeMap;
+
+use crate::FnvHashMap;
+
+/// A bounded cache of conversion results, which evicts the least recently used input.
+#[derive(Debug)]
+pub(crate) struct LruCache {
+    capacity: usize,
+    /// Input to converted string and the tick of its last use.
+    map: FnvHashMap<String, (String, u64)>,
+    /// Tick of the last use to input, the first entry is the least recently used.
+    recency: BTreeMap<u64, String>,
+    tick: u64,
+}
+
+impl LruCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        LruCache {
+            capacity,
+            map: FnvHashMap::default(),
+            recency: BTreeMap::new(),
+            tick: 0,
+        }
+    }
+
+    pub(crate) fn get(&mut self, input: &str) -> Option<String> {
+        self.tick += 1;
+        let (converted, last_used) = self.map.get_mut(input)?;
+        let key = self
+            .recency
+            .remove(last_used)
+            .expect("recency is in sync with map");
+        *last_used = self.tick;
+        self.recency.insert(self.tick, key);
+        Some(converted.clone())
+    }
+
+    pub(crate) fn insert(&mut self, input: &str, converted: String) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.tick += 1;
+        if let Some((_, last_used)) = self.map.remove(input) {
+            self.recency.remove(&last_used);
+        } else if self.map.len() >= self.capacity {
+            if let Some((_, evicted)) = self.recency.pop_first() {
+                self.map.remove(&evicted);
+            }
+        }
+        self.map.insert(input.to_string(), (converted, self.tick));
+        self.recency.insert(self.tick, input.to_string());
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.map.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut cache = LruCache::new(2);
+        cache.insert("a", "A".to_string());
+        cache.insert("b", "B".to_string());
+        assert_eq!(cache.get("a").as_deref(), Some("A"));
+        cache.insert("c", "C".to_string());
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.get("a").as_deref(), Some("A"));
+        assert_eq!(cache.get("c").as_deref(), Some("C"));
+        cache.insert("c", "C2".to_string());
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get("c").as_deref(), Some("C2"));
+    }
+
+    #[test]
+    fn zero_capacity() {
+        let mut cache = LruCache::new(0);
+        cache.insert("a", "A".to_string());
+        assert_eq!(cache.get("a"), None);
+    }
+}
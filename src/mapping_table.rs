@@ -0,0 +1,732 @@
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::char_map::sorted_keys;
+use crate::{convert_cow, CharMap, Entry, FnvHashMap, FnvHashSet, TargetScript};
+
+/// An owned mapping table, e.g. loaded with [load_mapping_from_str].
+///
+/// It provides the same conversions as the free functions of this crate, which use the mapping
+/// table embedded in the crate.
+#[derive(Debug, Clone, Default)]
+pub struct MappingTable {
+    /// Every entry is stored once, the keys below are indices into it.
+    entries: Cow<'static, [Entry]>,
+    /// Japanese kanji, sorted.
+    japanese_keys: Cow<'static, [(char, u32)]>,
+    /// Simplified chinese candidates, sorted.
+    simplified_keys: Cow<'static, [(char, u32)]>,
+    /// Traditional chinese candidates, sorted.
+    traditional_keys: Cow<'static, [(char, u32)]>,
+}
+
+/// Error when parsing a mapping table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The line doesn't consist of the three tab separated fields japanese, traditional and
+    /// simplified.
+    InvalidFieldCount {
+        /// The line number, starting at 1.
+        line: usize,
+        /// The number of fields found.
+        count: usize,
+    },
+    /// The japanese field of the line is empty.
+    MissingJapanese {
+        /// The line number, starting at 1.
+        line: usize,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::InvalidFieldCount { line, count } => {
+                write!(
+                    f,
+                    "line {line}: expected 3 tab separated fields, found {count}"
+                )
+            }
+            ParseError::MissingJapanese { line } => {
+                write!(f, "line {line}: the japanese field is empty")
+            }
+        }
+    }
+}
+
+impl core::error::Error for ParseError {}
+
+/// Error when loading a mapping table from a file, see [load_mapping_from_path].
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub enum LoadError {
+    /// The file couldn't be read, or isn't valid UTF-8.
+    Io(std::io::Error),
+    /// The content is not a valid mapping table.
+    Parse(ParseError),
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Io(err) => write!(f, "can't read the mapping table: {err}"),
+            LoadError::Parse(err) => write!(f, "invalid mapping table: {err}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoadError::Io(err) => Some(err),
+            LoadError::Parse(err) => Some(err),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for LoadError {
+    fn from(err: std::io::Error) -> Self {
+        LoadError::Io(err)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<ParseError> for LoadError {
+    fn from(err: ParseError) -> Self {
+        LoadError::Parse(err)
+    }
+}
+
+/// Statistics of a mapping table, see [MappingTable::stats].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MappingStats {
+    /// Number of entries, one per japanese kanji.
+    pub entries: usize,
+    /// Entries with more than one traditional chinese candidate.
+    pub multiple_traditional: usize,
+    /// Entries with more than one simplified chinese candidate.
+    pub multiple_simplified: usize,
+    /// Entries where the japanese kanji is the only traditional and simplified candidate.
+    pub identical: usize,
+    /// Entries with `N/A` as traditional chinese.
+    pub missing_traditional: usize,
+    /// Entries with `N/A` as simplified chinese.
+    pub missing_simplified: usize,
+}
+
+/// Parses a mapping table in the format of the embedded `kanji_mapping_table.txt`.
+///
+/// Every line consists of the tab separated fields japanese, traditional and simplified. Multiple
+/// candidates are separated by comma, `N/A` marks a missing candidate. Empty lines and a header
+/// enclosed in lines of dashes are skipped.
+///
+/// Unlike the embedded table, the entries are not filtered by the japanese kanji list.
+pub fn load_mapping_from_str(data: &str) -> Result<MappingTable, ParseError> {
+    let mut entries = Vec::new();
+    let mut in_header = false;
+    for (line_number, line) in data.lines().enumerate() {
+        if !line.is_empty() && line.chars().all(|cha| cha == '-') {
+            in_header = !in_header;
+            continue;
+        }
+        if in_header || line.trim().is_empty() {
+            continue;
+        }
+        let line_number = line_number + 1;
+        match Entry::from_line(line) {
+            Some(entry) => entries.push(entry),
+            None => return Err(line_error(line, line_number)),
+        }
+    }
+    Ok(MappingTable::from_entries(entries))
+}
+
+/// Reads and parses a mapping table file like [load_mapping_from_str], e.g. a data file that is
+/// updated separately from the binary.
+#[cfg(feature = "std")]
+pub fn load_mapping_from_path<P: AsRef<std::path::Path>>(
+    path: P,
+) -> Result<MappingTable, LoadError> {
+    let data = std::fs::read_to_string(path)?;
+    Ok(load_mapping_from_str(&data)?)
+}
+
+/// The reason why [Entry::from_line] failed on the line.
+pub(crate) fn line_error(line: &str, line_number: usize) -> ParseError {
+    let count = line.split('\t').count();
+    if count != 3 {
+        return ParseError::InvalidFieldCount {
+            line: line_number,
+            count,
+        };
+    }
+    ParseError::MissingJapanese { line: line_number }
+}
+
+impl MappingTable {
+    /// Creates a mapping table from entries.
+    ///
+    /// Every entry is keyed separately by its japanese kanji, its first traditional and its first
+    /// simplified candidate. For the first candidates the first entry wins.
+    ///
+    /// Further candidates are keys too, unless they are the japanese kanji or a first candidate
+    /// of any entry, so a char with an entry of its own resolves through it, e.g. '甚' is the
+    /// second candidate of '什', but has its own entry. If multiple entries claim a further
+    /// candidate, the entry where it has the lowest position in the candidate list wins, and on a
+    /// tie the first entry. For the japanese kanji the last entry wins.
+    pub fn from_entries(entries: impl IntoIterator<Item = Entry>) -> Self {
+        let entries: Vec<Entry> = entries.into_iter().collect();
+        let primary = primary_chars(&entries);
+        let mut japanese_keys = FnvHashMap::default();
+        let mut simplified_keys = FnvHashMap::default();
+        let mut traditional_keys = FnvHashMap::default();
+        for (index, entry) in entries.iter().enumerate() {
+            let index = index as u32;
+            japanese_keys.insert(entry.japanese, index);
+            let insert = |keys: &mut _, candidates: &[char]| {
+                insert_candidates(keys, candidates, index, &primary)
+            };
+            insert(&mut traditional_keys, &entry.traditional_chinese);
+            insert(&mut simplified_keys, &entry.simplified_chinese);
+        }
+        let without_position = |keys: FnvHashMap<char, (usize, u32)>| {
+            keys.into_iter()
+                .map(|(key, (_, index))| (key, index))
+                .collect()
+        };
+        MappingTable {
+            entries: Cow::Owned(entries),
+            japanese_keys: Cow::Owned(sorted_keys(japanese_keys)),
+            simplified_keys: Cow::Owned(sorted_keys(without_position(simplified_keys))),
+            traditional_keys: Cow::Owned(sorted_keys(without_position(traditional_keys))),
+        }
+    }
+
+    /// The mapping table generated at compile time by the build script. The keys need to be
+    /// sorted.
+    pub(crate) const fn from_static(
+        entries: &'static [Entry],
+        japanese_keys: &'static [(char, u32)],
+        simplified_keys: &'static [(char, u32)],
+        traditional_keys: &'static [(char, u32)],
+    ) -> Self {
+        MappingTable {
+            entries: Cow::Borrowed(entries),
+            japanese_keys: Cow::Borrowed(japanese_keys),
+            simplified_keys: Cow::Borrowed(simplified_keys),
+            traditional_keys: Cow::Borrowed(traditional_keys),
+        }
+    }
+
+    /// All entries, in the order of the mapping table.
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Keeps only the entries for which `keep` returns true, and rebuilds the keys like
+    /// [Self::from_entries]. This allows to skip entries that would win a key collision, e.g. the
+    /// strange entries of the dataset by keeping only the japanese kanji list, like the embedded
+    /// table does. The chars of the removed entries then resolve through an entry where they are
+    /// a candidate:
+    ///
+    /// ```
+    /// # #[cfg(feature = "direction-japanese")]
+    /// # {
+    /// use kanji_hanzi_converter::{is_known_kanji, load_mapping_from_str, TargetScript};
+    ///
+    /// let table = load_mapping_from_str("學\t學\t学\n学\t學\t学").unwrap();
+    /// assert_eq!(table.convert_char('學', TargetScript::Japanese), Some('學'));
+    /// let table = table.retain(|entry| is_known_kanji(entry.japanese));
+    /// assert_eq!(table.convert_char('學', TargetScript::Japanese), Some('学'));
+    /// # }
+    /// ```
+    pub fn retain(self, keep: impl FnMut(&Entry) -> bool) -> MappingTable {
+        let mut entries = self.entries.into_owned();
+        entries.retain(keep);
+        MappingTable::from_entries(entries)
+    }
+
+    /// Counts the entries by their candidates. Entries are counted once per japanese kanji, like
+    /// in [Self::japanese_map].
+    pub fn stats(&self) -> MappingStats {
+        let mut stats = MappingStats::default();
+        for entry in self.japanese_map().values() {
+            let traditional = &*entry.traditional_chinese;
+            let simplified = &*entry.simplified_chinese;
+            stats.entries += 1;
+            stats.multiple_traditional += usize::from(traditional.len() > 1);
+            stats.multiple_simplified += usize::from(simplified.len() > 1);
+            stats.identical +=
+                usize::from(traditional == [entry.japanese] && simplified == [entry.japanese]);
+            stats.missing_traditional += usize::from(traditional.is_empty());
+            stats.missing_simplified += usize::from(simplified.is_empty());
+        }
+        stats
+    }
+
+    /// Entries keyed by their japanese kanji.
+    pub fn japanese_map(&self) -> CharMap<'_> {
+        CharMap::new(&self.japanese_keys, &self.entries)
+    }
+
+    /// Entries keyed by their simplified chinese candidates.
+    pub fn simplified_map(&self) -> CharMap<'_> {
+        CharMap::new(&self.simplified_keys, &self.entries)
+    }
+
+    /// Entries keyed by their traditional chinese candidates.
+    pub fn traditional_map(&self) -> CharMap<'_> {
+        CharMap::new(&self.traditional_keys, &self.entries)
+    }
+
+    /// The entry used to convert a char to the target script.
+    ///
+    /// A japanese kanji always resolves to its own entry. Otherwise a char that is already in the
+    /// target script is preferred, so e.g. a char that is the simplified form of one entry and the
+    /// traditional form of another resolves to the first entry when converting to simplified.
+    pub(crate) fn entry_for(&self, cha: char, target: TargetScript) -> Option<&Entry> {
+        self.entry_index_for(cha, target.prefers_simplified())
+            .map(|index| &self.entries[index])
+    }
+
+    /// The index of the entry returned by [Self::entry_for]. The simplified forms are checked
+    /// before the traditional forms, if `simplified_first` is set.
+    fn entry_index_for(&self, cha: char, simplified_first: bool) -> Option<usize> {
+        let (first, second) = if simplified_first {
+            (self.simplified_map(), self.traditional_map())
+        } else {
+            (self.traditional_map(), self.simplified_map())
+        };
+        self.japanese_map()
+            .get_index(&cha)
+            .or_else(|| first.get_index(&cha))
+            .or_else(|| second.get_index(&cha))
+    }
+
+    /// See [crate::entry_id].
+    pub fn entry_id(&self, cha: char) -> Option<usize> {
+        self.entry_index_for(cha, false)
+    }
+
+    /// See [crate::lookup].
+    pub fn lookup(&self, cha: char) -> Option<&Entry> {
+        self.entry_index_for(cha, false)
+            .map(|index| &self.entries[index])
+    }
+
+    /// See [crate::all_traditional_candidates].
+    #[cfg(feature = "direction-traditional")]
+    pub fn all_traditional_candidates(&self, cha: char) -> &[char] {
+        self.entry_for(cha, TargetScript::Traditional)
+            .map(|entry| &*entry.traditional_chinese)
+            .unwrap_or_default()
+    }
+
+    /// See [crate::all_simplified_candidates].
+    #[cfg(feature = "direction-simplified")]
+    pub fn all_simplified_candidates(&self, cha: char) -> &[char] {
+        self.entry_for(cha, TargetScript::Simplified)
+            .map(|entry| &*entry.simplified_chinese)
+            .unwrap_or_default()
+    }
+
+    /// See [crate::convert_char_all_traditional].
+    #[cfg(feature = "direction-traditional")]
+    pub fn convert_char_all_traditional(&self, cha: char) -> Vec<char> {
+        self.candidates_of_all_entries(cha, |entry| &entry.traditional_chinese)
+    }
+
+    /// See [crate::convert_char_all_simplified].
+    #[cfg(feature = "direction-simplified")]
+    pub fn convert_char_all_simplified(&self, cha: char) -> Vec<char> {
+        self.candidates_of_all_entries(cha, |entry| &entry.simplified_chinese)
+    }
+
+    /// See [crate::japanese_candidates].
+    #[cfg(feature = "direction-japanese")]
+    pub fn japanese_candidates(&self, cha: char) -> Vec<char> {
+        let mut all = Vec::new();
+        let references = |entry: &&Entry| {
+            entry.traditional_chinese.contains(&cha) || entry.simplified_chinese.contains(&cha)
+        };
+        for entry in self.entries.iter().filter(references) {
+            if !all.contains(&entry.japanese) {
+                all.push(entry.japanese);
+            }
+        }
+        all
+    }
+
+    /// See [crate::is_ambiguous].
+    pub fn is_ambiguous(&self, cha: char, target: TargetScript) -> bool {
+        let Some(entry) = self.entry_for(cha, target) else {
+            return false;
+        };
+        let candidates: usize = match target {
+            #[cfg(feature = "direction-japanese")]
+            TargetScript::Japanese => 1,
+            #[cfg(feature = "direction-traditional")]
+            TargetScript::Traditional => entry.traditional_chinese.len(),
+            #[cfg(feature = "direction-simplified")]
+            TargetScript::Simplified => entry.simplified_chinese.len(),
+        };
+        let other_entry = [
+            self.japanese_map(),
+            self.traditional_map(),
+            self.simplified_map(),
+        ]
+        .iter()
+        .filter_map(|map| map.get(&cha))
+        .any(|other| !core::ptr::eq(other, entry));
+        candidates > 1 || other_entry
+    }
+
+    /// Collects the candidates of every entry that references `cha`, in the order of the mapping
+    /// table, without duplicates.
+    #[cfg(any(feature = "direction-simplified", feature = "direction-traditional"))]
+    fn candidates_of_all_entries(
+        &self,
+        cha: char,
+        candidates: impl Fn(&Entry) -> &[char],
+    ) -> Vec<char> {
+        let mut all = Vec::new();
+        let references = |entry: &&Entry| {
+            entry.japanese == cha
+                || entry.traditional_chinese.contains(&cha)
+                || entry.simplified_chinese.contains(&cha)
+        };
+        for entry in self.entries.iter().filter(references) {
+            for candidate in candidates(entry) {
+                if !all.contains(candidate) {
+                    all.push(*candidate);
+                }
+            }
+        }
+        all
+    }
+
+    /// See [crate::convert_char].
+    pub fn convert_char(&self, cha: char, target: TargetScript) -> Option<char> {
+        match target {
+            #[cfg(feature = "direction-japanese")]
+            TargetScript::Japanese => self.convert_char_to_japanese(cha),
+            #[cfg(feature = "direction-traditional")]
+            TargetScript::Traditional => self.convert_char_to_traditional(cha),
+            #[cfg(feature = "direction-simplified")]
+            TargetScript::Simplified => self.convert_char_to_simplified(cha),
+        }
+    }
+
+    /// See [crate::convert_char_to_traditional].
+    #[cfg(feature = "direction-traditional")]
+    pub fn convert_char_to_traditional(&self, cha: char) -> Option<char> {
+        self.pick_candidate(cha, self.all_traditional_candidates(cha))
+    }
+
+    /// See [crate::convert_char_to_simplified].
+    #[cfg(feature = "direction-simplified")]
+    pub fn convert_char_to_simplified(&self, cha: char) -> Option<char> {
+        self.pick_candidate(cha, self.all_simplified_candidates(cha))
+    }
+
+    /// Picks the first candidate, unless `cha` is not a japanese kanji and already one of the
+    /// candidates.
+    #[cfg(any(feature = "direction-simplified", feature = "direction-traditional"))]
+    fn pick_candidate(&self, cha: char, candidates: &[char]) -> Option<char> {
+        if !self.japanese_map().contains_key(&cha) && candidates.contains(&cha) {
+            return Some(cha);
+        }
+        candidates.first().copied()
+    }
+
+    /// See [crate::convert_char_to_japanese].
+    #[cfg(feature = "direction-japanese")]
+    pub fn convert_char_to_japanese(&self, cha: char) -> Option<char> {
+        self.entry_for(cha, TargetScript::Japanese)
+            .map(|entry| entry.japanese)
+    }
+
+    /// See [crate::normalize_japanese].
+    #[cfg(feature = "direction-japanese")]
+    pub fn normalize_japanese(&self, input: &str) -> String {
+        input
+            .chars()
+            .map(|cha| {
+                if self.japanese_map().contains_key(&cha) {
+                    return cha;
+                }
+                self.traditional_map()
+                    .get(&cha)
+                    .map(|entry| entry.japanese)
+                    .unwrap_or(cha)
+            })
+            .collect()
+    }
+
+    /// See [crate::convert_to_traditional_chinese].
+    #[cfg(feature = "direction-traditional")]
+    pub fn convert_to_traditional_chinese(&self, input: &str) -> String {
+        input
+            .chars()
+            .map(|cha| self.convert_char_to_traditional(cha).unwrap_or(cha))
+            .collect()
+    }
+
+    /// See [crate::convert_to_simplified_chinese].
+    #[cfg(feature = "direction-simplified")]
+    pub fn convert_to_simplified_chinese(&self, input: &str) -> String {
+        input
+            .chars()
+            .map(|cha| self.convert_char_to_simplified(cha).unwrap_or(cha))
+            .collect()
+    }
+
+    /// See [crate::convert_to_japanese_kanji].
+    #[cfg(feature = "direction-japanese")]
+    pub fn convert_to_japanese_kanji(&self, input: &str) -> String {
+        input
+            .chars()
+            .map(|cha| self.convert_char_to_japanese(cha).unwrap_or(cha))
+            .collect()
+    }
+
+    /// See [crate::convert_to_traditional_chinese_cow].
+    #[cfg(feature = "direction-traditional")]
+    pub fn convert_to_traditional_chinese_cow<'a>(&self, input: &'a str) -> Cow<'a, str> {
+        convert_cow(input, |cha| self.convert_char_to_traditional(cha))
+    }
+
+    /// See [crate::convert_to_simplified_chinese_cow].
+    #[cfg(feature = "direction-simplified")]
+    pub fn convert_to_simplified_chinese_cow<'a>(&self, input: &'a str) -> Cow<'a, str> {
+        convert_cow(input, |cha| self.convert_char_to_simplified(cha))
+    }
+
+    /// See [crate::convert_to_japanese_kanji_cow].
+    #[cfg(feature = "direction-japanese")]
+    pub fn convert_to_japanese_kanji_cow<'a>(&self, input: &'a str) -> Cow<'a, str> {
+        convert_cow(input, |cha| self.convert_char_to_japanese(cha))
+    }
+
+    /// See [crate::needs_conversion].
+    pub fn needs_conversion(&self, input: &str, target: TargetScript) -> bool {
+        // The keys are sorted, so ASCII keys come first. Without any, like in the embedded table,
+        // the lookup of ASCII chars can be skipped
+        let ascii_keys = [
+            &self.japanese_keys,
+            &self.simplified_keys,
+            &self.traditional_keys,
+        ]
+        .iter()
+        .any(|keys| keys.first().is_some_and(|(key, _)| key.is_ascii()));
+        input.chars().any(|cha| {
+            (ascii_keys || !cha.is_ascii())
+                && self
+                    .convert_char(cha, target)
+                    .is_some_and(|conv| conv != cha)
+        })
+    }
+
+    /// See [crate::convert_simplified_to_traditional].
+    #[cfg(feature = "direction-traditional")]
+    pub fn convert_simplified_to_traditional(&self, input: &str) -> String {
+        input
+            .chars()
+            .map(|cha| {
+                self.simplified_map()
+                    .get(&cha)
+                    .and_then(|entry| entry.traditional_chinese.first())
+                    .copied()
+                    .unwrap_or(cha)
+            })
+            .collect()
+    }
+
+    /// See [crate::convert_traditional_to_simplified].
+    #[cfg(feature = "direction-simplified")]
+    pub fn convert_traditional_to_simplified(&self, input: &str) -> String {
+        input
+            .chars()
+            .map(|cha| {
+                self.traditional_map()
+                    .get(&cha)
+                    .and_then(|entry| entry.simplified_chinese.first())
+                    .copied()
+                    .unwrap_or(cha)
+            })
+            .collect()
+    }
+}
+
+/// The japanese kanji and the first candidates of the entries, which are the chars with a mapping
+/// of their own.
+fn primary_chars(entries: &[Entry]) -> FnvHashSet<char> {
+    let mut primary = FnvHashSet::default();
+    for entry in entries {
+        primary.insert(entry.japanese);
+        primary.extend(entry.traditional_chinese.first());
+        primary.extend(entry.simplified_chinese.first());
+    }
+    primary
+}
+
+/// Inserts the candidates of the entry at `index`, keeping the existing key if it has a lower or
+/// equal candidate position. Further candidates in `primary` are skipped.
+fn insert_candidates(
+    keys: &mut FnvHashMap<char, (usize, u32)>,
+    candidates: &[char],
+    index: u32,
+    primary: &FnvHashSet<char>,
+) {
+    for (position, val) in candidates.iter().enumerate() {
+        if position > 0 && primary.contains(val) {
+            continue;
+        }
+        let existing = keys.entry(*val).or_insert((position, index));
+        if position < existing.0 {
+            *existing = (position, index);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(all(
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn load_custom_mapping() {
+        let table = load_mapping_from_str("\n里\t裡,裏\t里\n为\t為\t为\n").unwrap();
+        assert_eq!(table.convert_to_traditional_chinese("里为"), "裡為");
+        assert_eq!(table.convert_to_japanese_kanji("裡為"), "里为");
+        assert_eq!(table.convert_traditional_to_simplified("裡"), "里");
+        assert_eq!(table.all_traditional_candidates('里'), &['裡', '裏']);
+        assert_eq!(table.convert_char_to_simplified('a'), None);
+    }
+
+    #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+    #[test]
+    fn needs_conversion_ascii_key() {
+        // A custom table may map ASCII, e.g. the fullwidth form of a letter
+        let table = load_mapping_from_str("Ａ\tＡ\tA\n").unwrap();
+        assert_eq!(table.convert_to_traditional_chinese("A"), "Ａ");
+        assert!(table.needs_conversion("A", TargetScript::Traditional));
+        assert!(table.needs_conversion("xAx", TargetScript::Traditional));
+        assert!(!table.needs_conversion("A", TargetScript::Simplified));
+        assert!(!table.needs_conversion("x", TargetScript::Traditional));
+    }
+
+    #[cfg(feature = "direction-simplified")]
+    #[test]
+    fn load_embedded_mapping() {
+        let table = load_mapping_from_str(include_str!("../kanji_mapping_table.txt")).unwrap();
+        assert_eq!(table.convert_to_simplified_chinese("醫生"), "医生");
+    }
+
+    #[cfg(feature = "direction-japanese")]
+    #[test]
+    fn retain_skips_strange_entry() {
+        // Lines 3365, 5055 and 5383 of the dataset
+        let table = load_mapping_from_str("學\t學\t学\n学\t學\t学\n斈\t學\t学").unwrap();
+        assert_eq!(table.lookup('學').unwrap().japanese, '學');
+        let table = table.retain(|entry| crate::is_known_kanji(entry.japanese));
+        assert_eq!(table.entries().len(), 1);
+        assert_eq!(table.lookup('學').unwrap().japanese, '学');
+        assert_eq!(table.convert_to_japanese_kanji("學斈"), "学斈");
+        // The embedded table is filtered the same way
+        assert_eq!(crate::lookup('學').unwrap().japanese, '学');
+    }
+
+    #[cfg(all(
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn further_candidates() {
+        let data = "七\t七,柒,漆\t七,柒,漆\n甚\t甚,什\t甚,什\n什\t什,甚\t什,甚";
+        let table = load_mapping_from_str(data).unwrap();
+        // 柒 has no entry of its own, so it resolves to the entry where it's a candidate
+        assert_eq!(table.convert_char('柒', TargetScript::Japanese), Some('七'));
+        assert_eq!(
+            table.convert_char('柒', TargetScript::Traditional),
+            Some('柒')
+        );
+        // 什 resolves through its own entry, and once it's removed through 甚
+        assert_eq!(table.convert_char('什', TargetScript::Japanese), Some('什'));
+        assert_eq!(table.convert_char('甚', TargetScript::Japanese), Some('甚'));
+        let table = table.retain(|entry| entry.japanese != '什');
+        assert_eq!(table.convert_char('什', TargetScript::Japanese), Some('甚'));
+        assert_eq!(
+            table.convert_char('什', TargetScript::Simplified),
+            Some('什')
+        );
+    }
+
+    #[cfg(all(feature = "std", feature = "direction-traditional"))]
+    #[test]
+    fn load_from_path() {
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("khc_mapping_{}.txt", std::process::id()));
+        std::fs::write(&path, "医\t醫\t医\n").unwrap();
+        let table = load_mapping_from_path(&path).unwrap();
+        assert_eq!(table.convert_to_traditional_chinese("医"), "醫");
+
+        std::fs::write(&path, "医\t醫\t医\n学\t學\n").unwrap();
+        let err = load_mapping_from_path(&path).unwrap_err();
+        assert!(matches!(
+            err,
+            LoadError::Parse(ParseError::InvalidFieldCount { line: 2, count: 2 })
+        ));
+        assert_eq!(
+            err.to_string(),
+            "invalid mapping table: line 2: expected 3 tab separated fields, found 2"
+        );
+        std::fs::remove_file(&path).unwrap();
+
+        let err = load_mapping_from_path(&path).unwrap_err();
+        assert!(matches!(err, LoadError::Io(err) if err.kind() == std::io::ErrorKind::NotFound));
+    }
+
+    #[test]
+    fn mapping_stats() {
+        let table = load_mapping_from_str("医\t醫,毉\t医\n人\t人\t人\n丼\tN/A\tN/A\n").unwrap();
+        assert_eq!(
+            table.stats(),
+            MappingStats {
+                entries: 3,
+                multiple_traditional: 1,
+                multiple_simplified: 0,
+                identical: 1,
+                missing_traditional: 1,
+                missing_simplified: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_error_empty_japanese() {
+        let err = load_mapping_from_str("学\t學\t学\n\t學\t学\n").unwrap_err();
+        assert_eq!(err, ParseError::MissingJapanese { line: 2 });
+        assert_eq!(err.to_string(), "line 2: the japanese field is empty");
+    }
+
+    #[test]
+    fn parse_error_line_number() {
+        let err = load_mapping_from_str("学\t學\t学\n\n学\t學\n").unwrap_err();
+        assert_eq!(err, ParseError::InvalidFieldCount { line: 3, count: 2 });
+        assert_eq!(
+            err.to_string(),
+            "line 3: expected 3 tab separated fields, found 2"
+        );
+    }
+}
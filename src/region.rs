@@ -0,0 +1,167 @@
+//! Regional variants of Traditional Chinese.
+//!
+//! The mapping table only has a general traditional chinese column. The regional variants are a
+//! small curated table on top of it, which replaces the general form with the form preferred in
+//! the region.
+use alloc::string::String;
+
+use crate::convert_char_to_traditional;
+
+/// A region with its own conventions for Traditional Chinese.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Region {
+    Taiwan,
+    HongKong,
+}
+
+/// `(general form, Taiwan form)`, sorted by the general form.
+static TAIWAN_VARIANTS: &[(char, char)] = &[
+    ('僞', '偽'),
+    ('啓', '啟'),
+    ('嫺', '嫻'),
+    ('峯', '峰'),
+    ('擡', '抬'),
+    ('棱', '稜'),
+    ('污', '汙'),
+    ('爲', '為'),
+    ('牀', '床'),
+    ('痹', '痺'),
+    ('癡', '痴'),
+    ('皁', '皂'),
+    ('着', '著'),
+    ('祕', '秘'),
+    ('竈', '灶'),
+    ('糉', '粽'),
+    ('纔', '才'),
+    ('羣', '群'),
+    ('脣', '唇'),
+    ('衆', '眾'),
+    ('裏', '裡'),
+    ('覈', '核'),
+    ('踊', '踴'),
+    ('鉢', '缽'),
+    ('鍼', '針'),
+    ('麪', '麵'),
+];
+
+/// `(general form, Hong Kong form)`, sorted by the general form.
+static HONG_KONG_VARIANTS: &[(char, char)] = &[
+    ('僞', '偽'),
+    ('兌', '兑'),
+    ('悅', '悦'),
+    ('戶', '户'),
+    ('擡', '抬'),
+    ('敘', '敍'),
+    ('柺', '拐'),
+    ('棱', '稜'),
+    ('檯', '枱'),
+    ('溫', '温'),
+    ('溼', '濕'),
+    ('爲', '為'),
+    ('癡', '痴'),
+    ('皁', '皂'),
+    ('祕', '秘'),
+    ('稅', '税'),
+    ('竈', '灶'),
+    ('糉', '粽'),
+    ('纔', '才'),
+    ('脣', '唇'),
+    ('蔥', '葱'),
+    ('蛻', '蜕'),
+    ('衆', '眾'),
+    ('衛', '衞'),
+    ('覈', '核'),
+    ('說', '説'),
+    ('踊', '踴'),
+    ('鉢', '缽'),
+    ('鉤', '鈎'),
+    ('銳', '鋭'),
+    ('鍼', '針'),
+    ('閱', '閲'),
+    ('麪', '麵'),
+];
+
+impl Region {
+    fn variants(self) -> &'static [(char, char)] {
+        match self {
+            Region::Taiwan => TAIWAN_VARIANTS,
+            Region::HongKong => HONG_KONG_VARIANTS,
+        }
+    }
+
+    /// Returns the regional form of a traditional chinese char, if it differs from the general
+    /// form.
+    pub fn variant(self, cha: char) -> Option<char> {
+        let variants = self.variants();
+        variants
+            .binary_search_by_key(&cha, |(general, _)| *general)
+            .ok()
+            .map(|pos| variants[pos].1)
+    }
+}
+
+/// Converts a single character to Traditional Chinese as used in the region.
+///
+/// Like [convert_char_to_traditional], but the result is replaced by the regional form if there
+/// is one. Without a regional form, the first general candidate is returned.
+pub fn convert_char_to_traditional_region(cha: char, region: Region) -> Option<char> {
+    let general = convert_char_to_traditional(cha);
+    region.variant(general.unwrap_or(cha)).or(general)
+}
+
+/// Converts a string to Traditional Chinese as used in the region.
+/// Leaves chars unchanged that can't be converted.
+pub fn convert_to_traditional_chinese_region(input: &str, region: Region) -> String {
+    input
+        .chars()
+        .map(|cha| convert_char_to_traditional_region(cha, region).unwrap_or(cha))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variants_are_sorted() {
+        for region in [Region::Taiwan, Region::HongKong] {
+            let variants = region.variants();
+            assert!(variants.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        }
+    }
+
+    #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+    #[test]
+    fn regional_conversion() {
+        assert_eq!(
+            convert_to_traditional_chinese_region("卫生", Region::Taiwan),
+            "衛生"
+        );
+        assert_eq!(
+            convert_to_traditional_chinese_region("卫生", Region::HongKong),
+            "衞生"
+        );
+        assert_eq!(
+            convert_to_traditional_chinese_region("温说", Region::Taiwan),
+            "溫說"
+        );
+        assert_eq!(
+            convert_to_traditional_chinese_region("温说", Region::HongKong),
+            "温説"
+        );
+        // Unmapped chars with a regional form
+        assert_eq!(
+            convert_char_to_traditional_region('峯', Region::Taiwan),
+            Some('峰')
+        );
+        assert_eq!(
+            convert_char_to_traditional_region('a', Region::Taiwan),
+            None
+        );
+        // Falls back to the general form
+        assert_eq!(
+            convert_char_to_traditional_region('学', Region::HongKong),
+            Some('學')
+        );
+    }
+}
@@ -0,0 +1,307 @@
+use std::io::{self, Read, Write};
+
+#[cfg(feature = "tokio")]
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+#[cfg(feature = "direction-japanese")]
+use crate::convert_char_to_japanese;
+#[cfg(feature = "direction-simplified")]
+use crate::convert_char_to_simplified;
+#[cfg(feature = "direction-traditional")]
+use crate::convert_char_to_traditional;
+
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// Converts UTF-8 text from `reader` to Simplified Chinese and writes it to `writer`.
+///
+/// The input is processed in chunks, so it doesn't need to fit into memory. Returns an error of
+/// kind [io::ErrorKind::InvalidData] if the input is not valid UTF-8.
+#[cfg(feature = "direction-simplified")]
+pub fn convert_stream_to_simplified<R: Read, W: Write>(reader: R, writer: W) -> io::Result<()> {
+    convert_stream(reader, writer, convert_char_to_simplified)
+}
+
+/// Converts UTF-8 text from `reader` to Traditional Chinese and writes it to `writer`.
+///
+/// The input is processed in chunks, so it doesn't need to fit into memory. Returns an error of
+/// kind [io::ErrorKind::InvalidData] if the input is not valid UTF-8.
+#[cfg(feature = "direction-traditional")]
+pub fn convert_stream_to_traditional<R: Read, W: Write>(reader: R, writer: W) -> io::Result<()> {
+    convert_stream(reader, writer, convert_char_to_traditional)
+}
+
+/// Converts UTF-8 text from `reader` to Japanese Kanji and writes it to `writer`.
+///
+/// The input is processed in chunks, so it doesn't need to fit into memory. Returns an error of
+/// kind [io::ErrorKind::InvalidData] if the input is not valid UTF-8.
+#[cfg(feature = "direction-japanese")]
+pub fn convert_stream_to_japanese<R: Read, W: Write>(reader: R, writer: W) -> io::Result<()> {
+    convert_stream(reader, writer, convert_char_to_japanese)
+}
+
+/// Converts UTF-8 text from async `reader` to Simplified Chinese and writes it to `writer`.
+///
+/// Like [convert_stream_to_simplified], but for tokio's async I/O. Returns an error of kind
+/// [io::ErrorKind::InvalidData] if the input is not valid UTF-8.
+#[cfg(all(feature = "tokio", feature = "direction-simplified"))]
+pub async fn convert_async_to_simplified<R: AsyncRead + Unpin, W: AsyncWrite + Unpin>(
+    reader: R,
+    writer: W,
+) -> io::Result<()> {
+    convert_async(reader, writer, convert_char_to_simplified).await
+}
+
+/// Converts UTF-8 text from async `reader` to Traditional Chinese and writes it to `writer`.
+///
+/// Like [convert_stream_to_traditional], but for tokio's async I/O. Returns an error of kind
+/// [io::ErrorKind::InvalidData] if the input is not valid UTF-8.
+#[cfg(all(feature = "tokio", feature = "direction-traditional"))]
+pub async fn convert_async_to_traditional<R: AsyncRead + Unpin, W: AsyncWrite + Unpin>(
+    reader: R,
+    writer: W,
+) -> io::Result<()> {
+    convert_async(reader, writer, convert_char_to_traditional).await
+}
+
+/// Converts UTF-8 text from async `reader` to Japanese Kanji and writes it to `writer`.
+///
+/// Like [convert_stream_to_japanese], but for tokio's async I/O. Returns an error of kind
+/// [io::ErrorKind::InvalidData] if the input is not valid UTF-8.
+#[cfg(all(feature = "tokio", feature = "direction-japanese"))]
+pub async fn convert_async_to_japanese<R: AsyncRead + Unpin, W: AsyncWrite + Unpin>(
+    reader: R,
+    writer: W,
+) -> io::Result<()> {
+    convert_async(reader, writer, convert_char_to_japanese).await
+}
+
+/// The chunk buffer shared by the sync and async streaming.
+struct Chunks {
+    buf: Vec<u8>,
+    /// Number of bytes in `buf`. After a chunk is converted, only the bytes of a char that
+    /// straddles the chunk boundary remain.
+    filled: usize,
+    out: String,
+}
+
+impl Chunks {
+    fn new() -> Self {
+        Chunks {
+            buf: vec![0u8; CHUNK_SIZE],
+            filled: 0,
+            out: String::new(),
+        }
+    }
+
+    /// The part of the buffer to read the next chunk into.
+    fn unfilled(&mut self) -> &mut [u8] {
+        &mut self.buf[self.filled..]
+    }
+
+    /// Converts the complete chars after `read` bytes were read into [Self::unfilled].
+    fn convert(
+        &mut self,
+        read: usize,
+        convert_char: impl Fn(char) -> Option<char>,
+    ) -> io::Result<&str> {
+        self.filled += read;
+        let text = match std::str::from_utf8(&self.buf[..self.filled]) {
+            Ok(text) => text,
+            // Incomplete char at the end, carry it into the next read
+            Err(err) if err.error_len().is_none() => {
+                std::str::from_utf8(&self.buf[..err.valid_up_to()]).unwrap()
+            }
+            Err(err) => return Err(io::Error::new(io::ErrorKind::InvalidData, err)),
+        };
+        self.out.clear();
+        self.out
+            .extend(text.chars().map(|cha| convert_char(cha).unwrap_or(cha)));
+
+        let consumed = text.len();
+        self.buf.copy_within(consumed..self.filled, 0);
+        self.filled -= consumed;
+        Ok(&self.out)
+    }
+
+    /// Checks that the input didn't end in the middle of a char.
+    fn finish(&self) -> io::Result<()> {
+        if self.filled != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "stream ended with an incomplete UTF-8 char",
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn convert_stream<R: Read, W: Write>(
+    mut reader: R,
+    mut writer: W,
+    convert_char: impl Fn(char) -> Option<char>,
+) -> io::Result<()> {
+    let mut chunks = Chunks::new();
+    loop {
+        let read = match reader.read(chunks.unfilled()) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+        writer.write_all(chunks.convert(read, &convert_char)?.as_bytes())?;
+    }
+    chunks.finish()?;
+    writer.flush()
+}
+
+#[cfg(feature = "tokio")]
+async fn convert_async<R: AsyncRead + Unpin, W: AsyncWrite + Unpin>(
+    mut reader: R,
+    mut writer: W,
+    convert_char: impl Fn(char) -> Option<char>,
+) -> io::Result<()> {
+    let mut chunks = Chunks::new();
+    loop {
+        let read = match reader.read(chunks.unfilled()).await {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+        let out = chunks.convert(read, &convert_char)?;
+        writer.write_all(out.as_bytes()).await?;
+    }
+    chunks.finish()?;
+    writer.flush().await
+}
+
+#[cfg(all(test, feature = "direction-simplified"))]
+mod tests {
+    use super::*;
+
+    /// Returns at most one byte per read.
+    #[cfg(all(
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    struct ByteReader<'a>(&'a [u8]);
+
+    #[cfg(all(
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    impl Read for ByteReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let Some((first, rest)) = self.0.split_first() else {
+                return Ok(0);
+            };
+            buf[0] = *first;
+            self.0 = rest;
+            Ok(1)
+        }
+    }
+
+    #[cfg(all(
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[test]
+    fn stream_one_byte_at_a_time() {
+        let mut out = Vec::new();
+        convert_stream_to_simplified(ByteReader("醫生 and 學校".as_bytes()), &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "医生 and 学校");
+
+        // 4 byte chars outside the BMP
+        let mut out = Vec::new();
+        convert_stream_to_simplified(ByteReader("𠮟醫𩸽".as_bytes()), &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "𠮟医𩸽");
+
+        let mut out = Vec::new();
+        convert_stream_to_traditional(ByteReader("学校".as_bytes()), &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "學校");
+
+        let mut out = Vec::new();
+        convert_stream_to_japanese(ByteReader("學校".as_bytes()), &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "学校");
+    }
+
+    #[cfg(all(feature = "direction-simplified", feature = "direction-traditional"))]
+    #[test]
+    fn stream_large_input() {
+        let input = "醫生".repeat(CHUNK_SIZE);
+        let mut out = Vec::new();
+        convert_stream_to_simplified(input.as_bytes(), &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "医生".repeat(CHUNK_SIZE));
+    }
+
+    /// Returns a duplex stream which a spawned task writes the input into. The buffer of the
+    /// stream is tiny, so the chars are split across the reads of the conversion.
+    #[cfg(all(
+        feature = "tokio",
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    fn duplex(input: &'static [u8]) -> tokio::io::DuplexStream {
+        use tokio::io::AsyncWriteExt;
+
+        let (mut client, server) = tokio::io::duplex(3);
+        tokio::spawn(async move { client.write_all(input).await.unwrap() });
+        server
+    }
+
+    #[cfg(all(
+        feature = "tokio",
+        feature = "direction-japanese",
+        feature = "direction-simplified",
+        feature = "direction-traditional"
+    ))]
+    #[tokio::test(flavor = "current_thread")]
+    async fn async_chunk_boundaries() {
+        let mut out = Vec::new();
+        convert_async_to_simplified(duplex("醫生 and 學校".as_bytes()), &mut out)
+            .await
+            .unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "医生 and 学校");
+
+        // 4 byte chars outside the BMP
+        let mut out = Vec::new();
+        convert_async_to_simplified(duplex("𠮟醫𩸽".as_bytes()), &mut out)
+            .await
+            .unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "𠮟医𩸽");
+
+        let mut out = Vec::new();
+        convert_async_to_traditional(duplex("学校".as_bytes()), &mut out)
+            .await
+            .unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "學校");
+
+        let mut out = Vec::new();
+        convert_async_to_japanese(duplex("學校".as_bytes()), &mut out)
+            .await
+            .unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "学校");
+
+        // Truncated "學"
+        let err = convert_async_to_simplified(duplex(&"學".as_bytes()[..2]), &mut Vec::new())
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[cfg(feature = "direction-simplified")]
+    #[test]
+    fn stream_invalid_utf8() {
+        let mut out = Vec::new();
+        let err = convert_stream_to_simplified(&[b'a', 0xff, b'b'][..], &mut out).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        // Truncated "學"
+        let err = convert_stream_to_simplified(&"學".as_bytes()[..2], &mut out).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
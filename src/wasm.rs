@@ -0,0 +1,48 @@
+//! WebAssembly bindings, enabled with the `wasm` feature.
+//!
+//! The functions take and return JS strings and are exported in camel case, e.g.
+//! `convertToSimplifiedChinese`. The mapping table is embedded in the `.wasm` file.
+//!
+//! To use it from JS, build the crate e.g. as WebAssembly module with
+//! `cargo rustc --release --target wasm32-unknown-unknown --features wasm --crate-type cdylib`,
+//! and generate the JS glue with `wasm-bindgen --target web` on the `.wasm` file.
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// Converts the input to Simplified Chinese, see [crate::convert_to_simplified_chinese].
+#[cfg(feature = "direction-simplified")]
+#[wasm_bindgen(js_name = convertToSimplifiedChinese)]
+pub fn convert_to_simplified_chinese(input: &str) -> String {
+    crate::convert_to_simplified_chinese(input)
+}
+
+/// Converts the input to Traditional Chinese, see [crate::convert_to_traditional_chinese].
+#[cfg(feature = "direction-traditional")]
+#[wasm_bindgen(js_name = convertToTraditionalChinese)]
+pub fn convert_to_traditional_chinese(input: &str) -> String {
+    crate::convert_to_traditional_chinese(input)
+}
+
+/// Converts the input to Japanese Kanji, see [crate::convert_to_japanese_kanji].
+#[cfg(feature = "direction-japanese")]
+#[wasm_bindgen(js_name = convertToJapaneseKanji)]
+pub fn convert_to_japanese_kanji(input: &str) -> String {
+    crate::convert_to_japanese_kanji(input)
+}
+
+#[cfg(all(
+    test,
+    feature = "direction-japanese",
+    feature = "direction-simplified",
+    feature = "direction-traditional"
+))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_through_bindings() {
+        assert_eq!(convert_to_simplified_chinese("醫生 學校"), "医生 学校");
+        assert_eq!(convert_to_japanese_kanji("醫生 學校"), "医生 学校");
+        assert_eq!(convert_to_traditional_chinese("学校"), "學校");
+        assert_eq!(convert_to_simplified_chinese(""), "");
+    }
+}